@@ -0,0 +1,129 @@
+use crate::data_reader::RRSeries;
+use crate::samp_en::{calc_cosen, calc_qse, calc_samp_en_estimate};
+
+/// Settings of the Sliding-Window Atrial Fibrillation Detector
+///
+/// The defaults follow Lake and Moorman (2011) and assume RR intervals in ms:
+/// 12-beat segments, m = 1, r starting at 30 ms and growing until at least
+/// 5 matches of length m + 1 are found, and AF suspected for COSEn above -1.4.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AfDetectionConfig {
+    /// segment length in beats
+    pub window: usize,
+    /// shift between consecutive segments in beats
+    pub step: usize,
+    /// the template length (embedding dimension)
+    pub m: usize,
+    /// the initial radius of comparison
+    pub r: f64,
+    /// the increment of r when too few matches are found
+    pub r_step: f64,
+    /// the minimal number of matches of length m + 1
+    pub min_matches: u64,
+    /// the COSEn value above which a segment is flagged
+    pub threshold: f64,
+    /// steepness of the logistic mapping of COSEn to AF probability
+    pub slope: f64,
+    /// segments with more annotated beats than this are skipped
+    pub max_annotated: usize,
+}
+
+impl Default for AfDetectionConfig {
+    fn default() -> Self {
+        AfDetectionConfig {
+            window: 12,
+            step: 12,
+            m: 1,
+            r: 30.0,
+            r_step: 10.0,
+            min_matches: 5,
+            threshold: -1.4,
+            slope: 4.0,
+            max_annotated: 0,
+        }
+    }
+}
+
+/// One Analysed Segment
+#[derive(Debug, Clone, PartialEq)]
+pub struct AfSegment {
+    /// index of the first beat of the segment
+    pub start: usize,
+    /// index one past the last beat of the segment
+    pub end: usize,
+    /// time of the segment onset, the sum of the preceding RR intervals
+    pub onset: f64,
+    /// the radius of comparison finally used
+    pub r: f64,
+    /// the quadratic sample entropy of the segment
+    pub qse: f64,
+    /// the coefficient of sample entropy of the segment
+    pub cosen: f64,
+    /// logistic mapping of COSEn, 0.5 at the threshold
+    pub probability: f64,
+    /// true when COSEn exceeds the threshold
+    pub af: bool,
+}
+
+/// Detect Atrial Fibrillation in Sliding Windows
+///
+/// This function slides a window over the RR series, calculates QSE and COSEn
+/// in every segment, and flags the segments whose COSEn exceeds the threshold.
+/// The AF probability is 1 / (1 + exp(-slope * (COSEn - threshold))), a simple
+/// monotone score rather than a calibrated probability.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `config`: &AfDetectionConfig - the detector settings
+/// # Returns
+/// * Vec<AfSegment> - the analysed segments; segments with too many annotated beats are left out
+pub fn detect_af(series: &RRSeries, config: &AfDetectionConfig) -> Vec<AfSegment> {
+    let mut segments = Vec::new();
+    if config.window < config.m + 2 || config.step == 0 {
+        return segments;
+    }
+    let mut onset = 0.0;
+    let mut consumed = 0;
+    let mut start = 0;
+    while start + config.window <= series.rr.len() {
+        let end = start + config.window;
+        onset += series.rr[consumed..start].iter().sum::<f64>();
+        consumed = start;
+        let annotated = series.annot[start..end].iter().filter(|&&a| a != 0).count();
+        if annotated <= config.max_annotated {
+            segments.push(analyze_segment(
+                &series.rr[start..end],
+                start,
+                onset,
+                config,
+            ));
+        }
+        start += config.step;
+    }
+    segments
+}
+
+// calculating QSE and COSEn of one segment, growing r until enough matches are found
+fn analyze_segment(rr: &[f64], start: usize, onset: f64, config: &AfDetectionConfig) -> AfSegment {
+    let range = rr.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+        - rr.iter().cloned().fold(f64::INFINITY, f64::min);
+    let mut r = config.r;
+    let mut estimate = calc_samp_en_estimate(rr, config.m, r);
+    while estimate.a < config.min_matches && r < range && config.r_step > 0.0 {
+        r += config.r_step;
+        estimate = calc_samp_en_estimate(rr, config.m, r);
+    }
+    let qse = calc_qse(rr, config.m, r);
+    let cosen = calc_cosen(rr, config.m, r);
+    let probability = 1.0 / (1.0 + (-config.slope * (cosen - config.threshold)).exp());
+    AfSegment {
+        start,
+        end: start + rr.len(),
+        onset,
+        r,
+        qse,
+        cosen,
+        probability,
+        af: cosen > config.threshold,
+    }
+}
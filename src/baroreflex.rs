@@ -0,0 +1,190 @@
+use crate::runs::{NeutralTolerance, RunType, RunsConfig, SeriesRuns};
+use crate::stats::{linear_regression, mean};
+
+/// Settings of the Baroreflex Sequence Method
+///
+/// The defaults assume systolic pressures in mmHg and RR intervals in ms:
+/// beat-to-beat changes larger than 1 mmHg and 5 ms, sequences of at least
+/// 3 beats, and a correlation of at least 0.85 between SBP and RR in a sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaroreflexConfig {
+    /// the RR interval paired with SBP of beat i is RR of beat i + lag
+    pub lag: usize,
+    /// SBP changes up to this value are not considered a rise or a fall
+    pub sbp_threshold: f64,
+    /// RR changes up to this value are not considered a rise or a fall
+    pub rr_threshold: f64,
+    /// the minimal number of beats in a sequence (and in an SBP ramp)
+    pub min_length: usize,
+    /// sequences with a lower correlation between SBP and RR are rejected, 0 accepts all
+    pub min_correlation: f64,
+}
+
+impl Default for BaroreflexConfig {
+    fn default() -> Self {
+        BaroreflexConfig {
+            lag: 0,
+            sbp_threshold: 1.0,
+            rr_threshold: 5.0,
+            min_length: 3,
+            min_correlation: 0.85,
+        }
+    }
+}
+
+/// Direction of a Baroreflex Sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SequenceType {
+    /// SBP and RR rise together
+    Up,
+    /// SBP and RR fall together
+    Down,
+}
+
+/// One Baroreflex Sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaroreflexSequence {
+    /// index of the first SBP value of the sequence
+    pub start: usize,
+    /// index of the last SBP value of the sequence; the RR intervals are start + lag..=end + lag
+    pub end: usize,
+    pub kind: SequenceType,
+    /// the slope of the regression of RR on SBP, e.g. in ms/mmHg
+    pub slope: f64,
+    /// the correlation between SBP and RR in the sequence
+    pub correlation: f64,
+}
+
+/// Result of the Baroreflex Sequence Method
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaroreflexSequences {
+    pub up: Vec<BaroreflexSequence>,
+    pub down: Vec<BaroreflexSequence>,
+    /// the number of SBP rises and falls of at least min_length beats
+    pub sbp_ramps: usize,
+    /// the mean slope of all sequences, NaN if there are none
+    pub brs: f64,
+    /// the mean slope of the up-sequences
+    pub brs_up: f64,
+    /// the mean slope of the down-sequences
+    pub brs_down: f64,
+    /// the baroreflex effectiveness index - the fraction of SBP ramps containing at least one sequence
+    pub bei: f64,
+}
+
+fn mean_slope(sequences: &[BaroreflexSequence]) -> f64 {
+    let slopes: Vec<f64> = sequences.iter().map(|s| s.slope).collect();
+    mean(&slopes)
+}
+
+/// Find Baroreflex Sequences in Aligned SBP and RR Series
+///
+/// This function implements the sequence method of spontaneous baroreflex
+/// sensitivity: it looks for runs of at least `min_length` beats in which SBP
+/// and the lagged RR interval rise (up-sequences) or fall (down-sequences)
+/// together, both changing by more than their thresholds at every beat. The
+/// steps are classified like the steps of the runs analysis, with the
+/// thresholds as absolute neutral tolerances.
+///
+/// # Parameters
+/// * `sbp`: &[f64] - systolic pressures, one per beat
+/// * `rr`: &[f64] - RR intervals aligned with `sbp`
+/// * `annotations`: Option<&[u8]> - beats with a non-zero annotation break the sequences and the ramps
+/// * `config`: &BaroreflexConfig - the method settings
+/// # Returns
+/// * BaroreflexSequences - the sequences with their slopes, the mean BRS and the BEI
+pub fn baroreflex_sequences(
+    sbp: &[f64],
+    rr: &[f64],
+    annotations: Option<&[u8]>,
+    config: &BaroreflexConfig,
+) -> BaroreflexSequences {
+    let size = sbp
+        .len()
+        .min(rr.len().saturating_sub(config.lag))
+        .min(annotations.map_or(usize::MAX, |a| a.len().saturating_sub(config.lag)));
+    // a pair (SBP[i], RR[i + lag]) is only used if both beats are normal
+    let normal = |i: usize| annotations.is_none_or(|a| a[i] == 0 && a[i + config.lag] == 0);
+    let sbp_config = RunsConfig {
+        neutral: NeutralTolerance::Absolute(config.sbp_threshold),
+        ..RunsConfig::default()
+    };
+    let rr_config = RunsConfig {
+        neutral: NeutralTolerance::Absolute(config.rr_threshold),
+        ..RunsConfig::default()
+    };
+    let min_steps = config.min_length.max(2) - 1;
+
+    // SBP ramps - SBP runs of at least min_length beats, in pairs with normal beats
+    let mask: Vec<u8> = (0..size).map(|i| u8::from(!normal(i))).collect();
+    let mut ramps = SeriesRuns::from_values(sbp[..size].to_vec(), Some(mask), sbp_config);
+    let ramp_beats: Vec<(usize, usize)> = ramps
+        .runs()
+        .filter(|run| run.kind != RunType::Neu && run.length >= min_steps)
+        .map(|run| (run.start, run.end))
+        .collect();
+
+    // common direction of the SBP and RR steps from pair i to pair i + 1
+    let step = |i: usize| {
+        if !normal(i) || !normal(i + 1) {
+            return None;
+        }
+        let direction = sbp_config.classify(sbp[i], sbp[i + 1])?;
+        let rr_direction = rr_config.classify(rr[i + config.lag], rr[i + config.lag + 1])?;
+        match direction {
+            RunType::Neu => None,
+            _ if direction == rr_direction => Some(direction),
+            _ => None,
+        }
+    };
+
+    let mut up = Vec::new();
+    let mut down = Vec::new();
+    let mut start = 0;
+    for i in 0..size {
+        // the stretch of equal steps from `start` ends at beat i
+        if i + 1 < size && step(i).is_some() && step(i) == step(start) {
+            continue;
+        }
+        let direction = if i > start { step(start) } else { None };
+        if let Some(direction) = direction.filter(|_| i - start >= min_steps) {
+            let x = &sbp[start..=i];
+            let y = &rr[start + config.lag..=i + config.lag];
+            let (slope, _, correlation) = linear_regression(x, y);
+            if correlation >= config.min_correlation {
+                let sequence = BaroreflexSequence {
+                    start,
+                    end: i,
+                    kind: if direction == RunType::Dec {
+                        SequenceType::Up
+                    } else {
+                        SequenceType::Down
+                    },
+                    slope,
+                    correlation,
+                };
+                match sequence.kind {
+                    SequenceType::Up => up.push(sequence),
+                    SequenceType::Down => down.push(sequence),
+                }
+            }
+        }
+        start = i;
+    }
+
+    let all: Vec<BaroreflexSequence> = up.iter().chain(&down).cloned().collect();
+    // every sequence lies within one SBP ramp, which may hold several sequences
+    let effective_ramps = ramp_beats
+        .iter()
+        .filter(|&&(start, end)| all.iter().any(|s| s.start >= start && s.end <= end))
+        .count();
+    BaroreflexSequences {
+        brs: mean_slope(&all),
+        brs_up: mean_slope(&up),
+        brs_down: mean_slope(&down),
+        bei: effective_ramps as f64 / ramp_beats.len() as f64,
+        up,
+        down,
+        sbp_ramps: ramp_beats.len(),
+    }
+}
@@ -0,0 +1,276 @@
+use crate::stats::{linear_fit, mean, std_dev};
+
+/// Settings of the Correlation Dimension Estimation
+///
+/// Radii are given as fractions of the SD of the signal, like
+/// `Tolerance::SdFraction`; the defaults span 0.05-2 SD in 30 logarithmic
+/// steps for embedding dimensions 1 to 10.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationDimensionConfig {
+    pub embeddings: Vec<usize>,
+    /// the embedding delay
+    pub tau: usize,
+    /// the radii as fractions of the SD, in increasing order
+    pub radii: Vec<f64>,
+    /// pairs of vectors closer in time than this window are left out; 1 leaves out self-matches only
+    pub theiler: usize,
+    /// the largest spread (max - min) of the local slopes within a plateau
+    pub plateau_tolerance: f64,
+    /// the smallest number of local slopes forming a plateau
+    pub min_plateau: usize,
+}
+
+impl Default for CorrelationDimensionConfig {
+    fn default() -> Self {
+        CorrelationDimensionConfig {
+            embeddings: (1..=10).collect(),
+            tau: 1,
+            radii: log_radii(0.05, 2.0, 30),
+            theiler: 1,
+            plateau_tolerance: 0.2,
+            min_plateau: 3,
+        }
+    }
+}
+
+/// `count` radii spaced logarithmically from `min` to `max`
+pub fn log_radii(min: f64, max: f64, count: usize) -> Vec<f64> {
+    if count < 2 {
+        return vec![min; count];
+    }
+    let ratio = (max / min).ln() / (count - 1) as f64;
+    (0..count).map(|i| min * (ratio * i as f64).exp()).collect()
+}
+
+/// Correlation Integral at One Embedding Dimension
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationFit {
+    pub m: usize,
+    /// C(r) for every radius, the fraction of pairs within max-norm distance r
+    pub correlation: Vec<f64>,
+    /// slopes of ln C against ln r between consecutive radii
+    pub local_slopes: Vec<f64>,
+    /// the range of local slopes forming the longest plateau
+    pub plateau: Option<(usize, usize)>,
+    /// the mean local slope over the plateau, NaN without a plateau
+    pub d2: f64,
+}
+
+/// Result of the Correlation Dimension Estimation
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationDimension {
+    /// the radii in the units of the signal
+    pub radii: Vec<f64>,
+    pub fits: Vec<CorrelationFit>,
+    /// D2 of the largest embedding dimension with a plateau, NaN if there is none
+    pub d2: f64,
+}
+
+fn max_distance(signal: &[f64], i: usize, j: usize, m: usize, tau: usize) -> f64 {
+    (0..m)
+        .map(|k| (signal[i + k * tau] - signal[j + k * tau]).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Calculate the Correlation Integral
+///
+/// This function counts the pairs of embedding vectors within the max-norm
+/// distance r, like `ncm_correlation_sums` does for one r, for all radii at once,
+/// leaving out the pairs closer in time than the Theiler window.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `m`: usize - the embedding dimension
+/// * `tau`: usize - the embedding delay
+/// * `radii`: &[f64] - the radii in increasing order, in the units of the signal
+/// * `theiler`: usize - the Theiler window
+/// # Returns
+/// * Vec<f64> - C(r) for every radius
+pub fn correlation_integral(
+    signal: &[f64],
+    m: usize,
+    tau: usize,
+    radii: &[f64],
+    theiler: usize,
+) -> Vec<f64> {
+    let m = m.max(1);
+    let size = signal.len().saturating_sub((m - 1) * tau);
+    // numbers of pairs whose distance first falls within radius k
+    let mut histogram = vec![0u64; radii.len() + 1];
+    let mut pairs = 0u64;
+    for i in 0..size {
+        for j in (i + theiler.max(1))..size {
+            let distance = max_distance(signal, i, j, m, tau);
+            histogram[radii.partition_point(|&r| r < distance)] += 1;
+            pairs += 1;
+        }
+    }
+    histogram[..radii.len()]
+        .iter()
+        .scan(0u64, |count, &h| {
+            *count += h;
+            Some(*count as f64 / pairs as f64)
+        })
+        .collect()
+}
+
+// the longest stretch of local slopes whose spread stays within the tolerance
+fn find_plateau(slopes: &[f64], tolerance: f64, min_length: usize) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    for start in 0..slopes.len() {
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for (end, &slope) in slopes.iter().enumerate().skip(start) {
+            if !slope.is_finite() {
+                break;
+            }
+            min = min.min(slope);
+            max = max.max(slope);
+            if max - min > tolerance {
+                break;
+            }
+            let length = end + 1 - start;
+            if length >= min_length.max(1) && best.is_none_or(|(a, b)| length > b - a) {
+                best = Some((start, end + 1));
+            }
+        }
+    }
+    best
+}
+
+/// Estimate the Correlation Dimension D2
+///
+/// This function implements the Grassberger-Procaccia algorithm: C(r) is
+/// calculated for every embedding dimension, the local slopes of ln C against
+/// ln r are searched for the longest plateau, and D2 is the mean slope over it.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `config`: &CorrelationDimensionConfig - the embeddings, radii and plateau criteria
+/// # Returns
+/// * CorrelationDimension - C(r), the local slopes and D2 for every embedding dimension
+pub fn calc_correlation_dimension(
+    signal: &[f64],
+    config: &CorrelationDimensionConfig,
+) -> CorrelationDimension {
+    let sd = std_dev(signal);
+    let radii: Vec<f64> = config.radii.iter().map(|r| r * sd).collect();
+    let fits: Vec<CorrelationFit> = config
+        .embeddings
+        .iter()
+        .map(|&m| {
+            let correlation = correlation_integral(signal, m, config.tau, &radii, config.theiler);
+            let local_slopes: Vec<f64> = (1..radii.len())
+                .map(|k| {
+                    (correlation[k].ln() - correlation[k - 1].ln())
+                        / (radii[k].ln() - radii[k - 1].ln())
+                })
+                .collect();
+            let plateau = find_plateau(&local_slopes, config.plateau_tolerance, config.min_plateau);
+            let d2 = plateau.map_or(f64::NAN, |(a, b)| mean(&local_slopes[a..b]));
+            CorrelationFit {
+                m,
+                correlation,
+                local_slopes,
+                plateau,
+                d2,
+            }
+        })
+        .collect();
+    let d2 = fits
+        .iter()
+        .rev()
+        .find(|fit| fit.plateau.is_some())
+        .map_or(f64::NAN, |fit| fit.d2);
+    CorrelationDimension { radii, fits, d2 }
+}
+
+/// Settings of Rosenstein's Largest Lyapunov Exponent
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyapunovConfig {
+    pub m: usize,
+    pub tau: usize,
+    /// nearest neighbours closer in time than this window are not used, e.g. the mean period
+    pub theiler: usize,
+    /// the number of steps the divergence is followed for
+    pub max_steps: usize,
+    /// the range of steps fitted with a line
+    pub fit_start: usize,
+    pub fit_end: usize,
+}
+
+impl Default for LyapunovConfig {
+    fn default() -> Self {
+        LyapunovConfig {
+            m: 5,
+            tau: 1,
+            theiler: 10,
+            max_steps: 20,
+            fit_start: 0,
+            fit_end: 5,
+        }
+    }
+}
+
+/// Divergence of Nearest Neighbours
+#[derive(Debug, Clone, PartialEq)]
+pub struct LyapunovFit {
+    /// the mean logarithmic distance of the neighbour pairs after 0..=max_steps steps
+    pub divergence: Vec<f64>,
+    pub slope: f64,
+    pub intercept: f64,
+    /// the largest Lyapunov exponent per beat (per sample), i.e. the slope
+    pub lambda: f64,
+}
+
+/// Estimate the Largest Lyapunov Exponent
+///
+/// This function implements Rosenstein et al. (1993): every embedding vector
+/// is paired with its nearest neighbour (max norm) outside the Theiler window,
+/// the mean logarithm of their distance is followed for max_steps steps, and the
+/// exponent is the slope of a line fitted to this curve over fit_start..=fit_end.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `config`: &LyapunovConfig - the embedding, Theiler window and fit range
+/// # Returns
+/// * LyapunovFit - the divergence curve and the fitted exponent
+pub fn calc_lyapunov_rosenstein(signal: &[f64], config: &LyapunovConfig) -> LyapunovFit {
+    let m = config.m.max(1);
+    let size = signal.len().saturating_sub((m - 1) * config.tau);
+    let neighbours: Vec<Option<usize>> = (0..size)
+        .map(|i| {
+            (0..size)
+                .filter(|&j| i.abs_diff(j) >= config.theiler.max(1))
+                .map(|j| (j, max_distance(signal, i, j, m, config.tau)))
+                .filter(|&(_, d)| d > 0.0)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(j, _)| j)
+        })
+        .collect();
+    let divergence: Vec<f64> = (0..=config.max_steps)
+        .map(|step| {
+            let logs: Vec<f64> = neighbours
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &j)| {
+                    let j = j?;
+                    if i.max(j) + step >= size {
+                        return None;
+                    }
+                    let d = max_distance(signal, i + step, j + step, m, config.tau);
+                    (d > 0.0).then(|| d.ln())
+                })
+                .collect();
+            mean(&logs)
+        })
+        .collect();
+    let end = config.fit_end.min(config.max_steps);
+    let steps: Vec<f64> = (config.fit_start..=end).map(|s| s as f64).collect();
+    let (slope, intercept) = linear_fit(&steps, &divergence[config.fit_start.min(end)..=end]);
+    LyapunovFit {
+        divergence,
+        slope,
+        intercept,
+        lambda: slope,
+    }
+}
@@ -0,0 +1,40 @@
+/// Coarse-Grain a Series at a Given Scale
+///
+/// This function implements the coarse-graining of multiscale entropy (Costa et al. 2002):
+/// the series is divided into non-overlapping windows of `scale` values and each window
+/// is replaced by its mean. A trailing incomplete window is left out.
+///
+/// # Parameters
+/// * `values`: &[f64] - the series
+/// * `scale`: usize - the window length; scale 1 returns the series unchanged
+/// # Returns
+/// * Vec<f64> - the coarse-grained series of values.len() / scale points
+pub fn coarse_grain(values: &[f64], scale: usize) -> Vec<f64> {
+    if scale == 0 {
+        return Vec::new();
+    }
+    values
+        .chunks_exact(scale)
+        .map(|window| window.iter().sum::<f64>() / scale as f64)
+        .collect()
+}
+
+/// Coarse-Grain Annotations at a Given Scale
+///
+/// The windows are the same as in `coarse_grain`; a coarse-grained point is annotated
+/// (invalid) if any of its beats is, and it keeps the largest annotation of its window.
+///
+/// # Parameters
+/// * `annotations`: &[u8] - annotations of the series, 0 for normal beats
+/// * `scale`: usize - the window length
+/// # Returns
+/// * Vec<u8> - the annotations of the coarse-grained points
+pub fn coarse_grain_annotations(annotations: &[u8], scale: usize) -> Vec<u8> {
+    if scale == 0 {
+        return Vec::new();
+    }
+    annotations
+        .chunks_exact(scale)
+        .map(|window| window.iter().copied().max().unwrap_or(0))
+        .collect()
+}
@@ -40,4 +40,14 @@ impl RRSeries {
             annot,
         })
     }
+
+    // the RR intervals of the normal (not annotated) beats
+    pub fn normal_rr(&self) -> Vec<f64> {
+        self.rr
+            .iter()
+            .zip(&self.annot)
+            .filter(|(_, &a)| a == 0)
+            .map(|(&rr, _)| rr)
+            .collect()
+    }
 }
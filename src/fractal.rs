@@ -0,0 +1,185 @@
+use crate::stats::{linear_fit, mean};
+
+/// Log-Log Fit Behind a Fractal Measure
+///
+/// Holds the points of the log-log plot together with the fitted line,
+/// so that the scaling region can be checked visually.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FractalFit {
+    /// the scales - k for Higuchi, window or block sizes for Hurst
+    pub scales: Vec<usize>,
+    /// the natural logarithm of the x-coordinates of the plot
+    pub log_x: Vec<f64>,
+    /// the natural logarithm of the measured quantity at each scale
+    pub log_y: Vec<f64>,
+    pub slope: f64,
+    pub intercept: f64,
+    /// the fractal dimension or Hurst exponent derived from the slope
+    pub estimate: f64,
+}
+
+impl FractalFit {
+    fn new(scales: Vec<usize>, log_x: Vec<f64>, log_y: Vec<f64>, estimate: fn(f64) -> f64) -> Self {
+        let (slope, intercept) = linear_fit(&log_x, &log_y);
+        FractalFit {
+            scales,
+            log_x,
+            log_y,
+            slope,
+            intercept,
+            estimate: estimate(slope),
+        }
+    }
+
+    /// the fitted line at each point of the plot
+    pub fn fitted(&self) -> Vec<f64> {
+        self.log_x
+            .iter()
+            .map(|x| self.slope * x + self.intercept)
+            .collect()
+    }
+}
+
+// scales doubling from `first` as long as `fits` holds
+fn doubling_scales(first: usize, fits: impl Fn(usize) -> bool) -> Vec<usize> {
+    std::iter::successors(Some(first.max(1)), |&n| Some(2 * n))
+        .take_while(|&n| fits(n))
+        .collect()
+}
+
+/// Calculate the Higuchi Fractal Dimension
+///
+/// This function calculates the mean normalised curve length L(k) of the
+/// subseries x(m), x(m + k), x(m + 2k), ... for k = 1..=kmax (Higuchi 1988);
+/// the dimension is the slope of ln L(k) against ln(1/k).
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `kmax`: usize - the largest interval k
+/// # Returns
+/// * FractalFit - ln(1/k), ln L(k) and the fitted line; the estimate is the Higuchi dimension
+pub fn calc_higuchi_fd(signal: &[f64], kmax: usize) -> FractalFit {
+    let n = signal.len();
+    let scales: Vec<usize> = (1..=kmax).filter(|&k| n > k).collect();
+    let lengths: Vec<f64> = scales
+        .iter()
+        .map(|&k| {
+            let curves: Vec<f64> = (0..k)
+                .filter_map(|m| {
+                    let steps = (n - 1 - m) / k;
+                    if steps == 0 {
+                        return None;
+                    }
+                    let length: f64 = (1..=steps)
+                        .map(|i| (signal[m + i * k] - signal[m + (i - 1) * k]).abs())
+                        .sum();
+                    Some(length * (n - 1) as f64 / (steps * k) as f64 / k as f64)
+                })
+                .collect();
+            mean(&curves)
+        })
+        .collect();
+    FractalFit::new(
+        scales.clone(),
+        scales.iter().map(|&k| -(k as f64).ln()).collect(),
+        lengths.iter().map(|l| l.ln()).collect(),
+        |slope| slope,
+    )
+}
+
+/// Calculate the Katz Fractal Dimension
+///
+/// This function implements Katz (1988): D = log(n) / (log(n) + log(d / L)),
+/// where L is the total curve length (the sum of absolute differences), d the
+/// largest distance from the first point and n the number of steps. There is
+/// no fit behind this measure.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// # Returns
+/// * f64 - the Katz dimension, NaN for fewer than 2 points or a constant signal
+pub fn calc_katz_fd(signal: &[f64]) -> f64 {
+    if signal.len() < 2 {
+        return f64::NAN;
+    }
+    let length: f64 = signal.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+    let distance = signal
+        .iter()
+        .map(|x| (x - signal[0]).abs())
+        .fold(0.0, f64::max);
+    let steps = (signal.len() - 1) as f64;
+    steps.log10() / (steps.log10() + (distance / length).log10())
+}
+
+// rescaled range of one window - range of the cumulative deviations from the mean divided by the SD
+fn rescaled_range(window: &[f64]) -> Option<f64> {
+    let mean = mean(window);
+    let mut cumulative = 0.0;
+    let (mut min, mut max) = (0.0f64, 0.0f64);
+    let mut squares = 0.0;
+    for x in window {
+        cumulative += x - mean;
+        min = min.min(cumulative);
+        max = max.max(cumulative);
+        squares += (x - mean).powi(2);
+    }
+    let sd = (squares / window.len() as f64).sqrt();
+    (sd > 0.0).then(|| (max - min) / sd)
+}
+
+/// Calculate the Hurst Exponent by Rescaled-Range Analysis
+///
+/// This function averages R/S over non-overlapping windows of n values, for n
+/// doubling from `min_window` up to half of the signal length; the Hurst exponent
+/// is the slope of ln(R/S) against ln n.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `min_window`: usize - the smallest window length, e.g. 8
+/// # Returns
+/// * FractalFit - ln n, ln(R/S) and the fitted line; the estimate is the Hurst exponent
+pub fn calc_hurst_rs(signal: &[f64], min_window: usize) -> FractalFit {
+    let scales = doubling_scales(min_window.max(2), |n| 2 * n <= signal.len());
+    let rs: Vec<f64> = scales
+        .iter()
+        .map(|&n| {
+            let values: Vec<f64> = signal.chunks_exact(n).filter_map(rescaled_range).collect();
+            mean(&values)
+        })
+        .collect();
+    FractalFit::new(
+        scales.clone(),
+        scales.iter().map(|&n| (n as f64).ln()).collect(),
+        rs.iter().map(|v| v.ln()).collect(),
+        |slope| slope,
+    )
+}
+
+/// Calculate the Hurst Exponent by the Aggregated-Variance Method
+///
+/// This function calculates the variance of the means of non-overlapping blocks
+/// of m values, for m doubling from 1 as long as there are at least `min_blocks`
+/// blocks. The variance scales as m^(2H - 2), so H = 1 + slope / 2.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `min_blocks`: usize - the smallest number of blocks, e.g. 10
+/// # Returns
+/// * FractalFit - ln m, ln variance and the fitted line; the estimate is the Hurst exponent
+pub fn calc_hurst_aggvar(signal: &[f64], min_blocks: usize) -> FractalFit {
+    let scales = doubling_scales(1, |m| signal.len() / m >= min_blocks.max(2));
+    let variances: Vec<f64> = scales
+        .iter()
+        .map(|&m| {
+            let means: Vec<f64> = signal.chunks_exact(m).map(mean).collect();
+            let grand_mean = mean(&means);
+            means.iter().map(|x| (x - grand_mean).powi(2)).sum::<f64>() / means.len() as f64
+        })
+        .collect();
+    FractalFit::new(
+        scales.clone(),
+        scales.iter().map(|&m| (m as f64).ln()).collect(),
+        variances.iter().map(|v| v.ln()).collect(),
+        |slope| 1.0 + slope / 2.0,
+    )
+}
@@ -0,0 +1,183 @@
+use crate::data_reader::RRSeries;
+use crate::stats::{linear_fit, mean};
+
+/// Settings of Heart Rate Turbulence Analysis
+///
+/// The defaults follow Schmidt et al. (1999) and the 2008 consensus and assume RR
+/// intervals in ms: 5 sinus beats before and 15 after the VPB, a coupling interval
+/// at least 20% shorter and a compensatory pause at least 20% longer than the
+/// reference, and sinus intervals of 300-2000 ms changing by at most 200 ms from
+/// beat to beat and deviating by at most 20% from the reference.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HrtConfig {
+    /// the annotation of ventricular premature beats
+    pub vpb_annotation: u8,
+    /// the number of sinus intervals required before the VPB, their mean is the reference
+    pub before: usize,
+    /// the number of sinus intervals required after the compensatory pause
+    pub after: usize,
+    /// the number of consecutive intervals in the regressions of the turbulence slope
+    pub slope_beats: usize,
+    /// the minimal shortening of the coupling interval, as a fraction of the reference
+    pub min_prematurity: f64,
+    /// the minimal lengthening of the compensatory pause, as a fraction of the reference
+    pub min_pause: f64,
+    /// sinus intervals out of min_rr..=max_rr exclude the event
+    pub min_rr: f64,
+    pub max_rr: f64,
+    /// a larger change between consecutive sinus intervals excludes the event
+    pub max_rr_change: f64,
+    /// a larger deviation of a sinus interval from the reference, as a fraction, excludes the event
+    pub max_reference_deviation: f64,
+}
+
+impl Default for HrtConfig {
+    fn default() -> Self {
+        HrtConfig {
+            vpb_annotation: 1,
+            before: 5,
+            after: 15,
+            slope_beats: 5,
+            min_prematurity: 0.2,
+            min_pause: 0.2,
+            min_rr: 300.0,
+            max_rr: 2000.0,
+            max_rr_change: 200.0,
+            max_reference_deviation: 0.2,
+        }
+    }
+}
+
+/// One Turbulence Event
+#[derive(Debug, Clone, PartialEq)]
+pub struct HrtEvent {
+    /// index of the VPB, i.e. of its coupling interval
+    pub index: usize,
+    /// the mean of the sinus intervals before the VPB
+    pub reference: f64,
+    pub coupling: f64,
+    pub pause: f64,
+    /// turbulence onset in %
+    pub onset: f64,
+    /// turbulence slope, e.g. in ms/beat
+    pub slope: f64,
+}
+
+/// Result of Heart Rate Turbulence Analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hrt {
+    /// the events passing the exclusion criteria
+    pub events: Vec<HrtEvent>,
+    /// the mean turbulence onset of the events, NaN without events
+    pub onset: f64,
+    /// the turbulence slope of the averaged tachogram, NaN without events
+    pub slope: f64,
+    /// the averaged intervals from `before` beats before the VPB to `after` beats after
+    /// the compensatory pause; the coupling interval is at index `before`
+    pub tachogram: Vec<f64>,
+}
+
+// turbulence onset in % - the two intervals after the pause relative to the two before the VPB
+fn turbulence_onset(tachogram: &[f64], vpb: usize) -> f64 {
+    let before = tachogram[vpb - 2] + tachogram[vpb - 1];
+    let after = tachogram[vpb + 2] + tachogram[vpb + 3];
+    (after - before) / before * 100.0
+}
+
+// turbulence slope - the steepest regression slope over `beats` consecutive intervals after the pause
+fn turbulence_slope(intervals: &[f64], beats: usize) -> f64 {
+    let x: Vec<f64> = (0..beats).map(|i| i as f64).collect();
+    intervals
+        .windows(beats)
+        .map(|y| linear_fit(&x, y).0)
+        .fold(f64::NAN, f64::max)
+}
+
+// checking the exclusion criteria of the event at the VPB v, returning its reference interval
+fn check_event(series: &RRSeries, v: usize, config: &HrtConfig) -> Option<f64> {
+    let rr = &series.rr;
+    let start = v.checked_sub(config.before)?;
+    let end = v + 2 + config.after;
+    if end > rr.len().min(series.annot.len()) || series.annot[v] != config.vpb_annotation {
+        return None;
+    }
+    // isolated VPB - all other beats, the compensatory pause included, are sinus beats
+    if (start..end).any(|i| i != v && series.annot[i] != 0) {
+        return None;
+    }
+    let reference = mean(&rr[start..v]);
+    if rr[v] > (1.0 - config.min_prematurity) * reference
+        || rr[v + 1] < (1.0 + config.min_pause) * reference
+    {
+        return None;
+    }
+    let sinus_ok = |intervals: &[f64]| {
+        intervals.iter().all(|&x| {
+            x >= config.min_rr
+                && x <= config.max_rr
+                && (x - reference).abs() <= config.max_reference_deviation * reference
+        }) && intervals
+            .windows(2)
+            .all(|pair| (pair[1] - pair[0]).abs() <= config.max_rr_change)
+    };
+    if sinus_ok(&rr[start..v]) && sinus_ok(&rr[v + 2..end]) {
+        Some(reference)
+    } else {
+        None
+    }
+}
+
+/// Calculate Heart Rate Turbulence
+///
+/// This function finds isolated ventricular premature beats with enough sinus
+/// context, applies the exclusion criteria, and calculates turbulence onset (TO)
+/// and turbulence slope (TS) for every event. The overall TO is the mean over
+/// events and the overall TS is calculated from the averaged tachogram.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals; the annotation of a VPB marks its coupling interval
+/// * `config`: &HrtConfig - the context lengths and exclusion criteria
+/// # Returns
+/// * Hrt - the accepted events, the overall TO and TS, and the averaged tachogram
+pub fn calc_hrt(series: &RRSeries, config: &HrtConfig) -> Hrt {
+    let length = config.before + 2 + config.after;
+    let mut events = Vec::new();
+    let mut tachogram = vec![0.0; length];
+    if config.before >= 2 && config.after >= config.slope_beats.max(2) && config.slope_beats >= 2 {
+        for v in 0..series.rr.len() {
+            let Some(reference) = check_event(series, v, config) else {
+                continue;
+            };
+            let intervals = &series.rr[v - config.before..v + 2 + config.after];
+            for (average, rr) in tachogram.iter_mut().zip(intervals) {
+                *average += rr;
+            }
+            events.push(HrtEvent {
+                index: v,
+                reference,
+                coupling: series.rr[v],
+                pause: series.rr[v + 1],
+                onset: turbulence_onset(intervals, config.before),
+                slope: turbulence_slope(&intervals[config.before + 2..], config.slope_beats),
+            });
+        }
+    }
+    if events.is_empty() {
+        return Hrt {
+            events,
+            onset: f64::NAN,
+            slope: f64::NAN,
+            tachogram: Vec::new(),
+        };
+    }
+    for average in tachogram.iter_mut() {
+        *average /= events.len() as f64;
+    }
+    let onsets: Vec<f64> = events.iter().map(|event| event.onset).collect();
+    Hrt {
+        onset: mean(&onsets),
+        slope: turbulence_slope(&tachogram[config.before + 2..], config.slope_beats),
+        events,
+        tachogram,
+    }
+}
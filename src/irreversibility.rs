@@ -0,0 +1,242 @@
+use crate::coarse_grain::{coarse_grain, coarse_grain_annotations};
+use crate::data_reader::RRSeries;
+use crate::rng::Rng;
+use crate::runs::{RunType, RunsConfig};
+pub use crate::surrogates::SurrogateComparison;
+use crate::surrogates::{
+    compare, compare_reversed, generate_surrogates, SurrogateConfig, SurrogateMethod,
+};
+
+/// Irreversibility Indices at One Scale
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrreversibilityIndices {
+    pub scale: usize,
+    /// the number of increments between normal coarse-grained points
+    pub increments: usize,
+    /// Costa's asymmetry A - (accelerations - decelerations) / increments
+    pub asymmetry: f64,
+    /// Porta's index - % of accelerations among the non-zero increments
+    pub porta: f64,
+    /// Guzik's index - % of the squared increments contributed by decelerations
+    pub guzik: f64,
+}
+
+/// Irreversibility Indices over Scales
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiscaleIrreversibility {
+    pub scales: Vec<IrreversibilityIndices>,
+    /// Costa's multiscale asymmetry index - the sum of A over scales
+    pub asymmetry_index: f64,
+    /// the sum over scales of Porta's index minus 50
+    pub porta_index: f64,
+    /// the sum over scales of Guzik's index minus 50
+    pub guzik_index: f64,
+}
+
+/// Null Hypothesis of the Surrogate Test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IrreversibilityNull {
+    /// the normal beats shuffled among themselves
+    Shuffled { surrogates: usize, seed: u64 },
+    /// the sign of every increment between normal beats flipped with probability 1/2, keeping
+    /// its magnitude. The surrogate wanders off like a random walk, so it is no model of the RR
+    /// series itself; but the indices only see the signs and sizes of the increments of the
+    /// coarse-grained series, whose distribution this makes symmetric, i.e. reversible
+    SignFlipped { surrogates: usize, seed: u64 },
+    /// the series reversed in time together with its annotations. A and the Porta and Guzik
+    /// indices minus 50 change sign under reversal, so the observed indices are set against
+    /// those of the reversed series, without SD, z-score or p-value
+    TimeReversed,
+}
+
+/// Result of the Surrogate Test
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrreversibilityTest {
+    pub null: IrreversibilityNull,
+    pub asymmetry_index: SurrogateComparison,
+    pub porta_index: SurrogateComparison,
+    pub guzik_index: SurrogateComparison,
+}
+
+/// Calculate Irreversibility Indices at One Scale
+///
+/// This function coarse-grains the series like multiscale entropy, with a coarse-grained
+/// point annotated if any of its beats is, and calculates Costa's asymmetry, Porta's
+/// and Guzik's indices from the increments between normal points. Increments are
+/// classified like the steps of the runs analysis; zero increments only enter the
+/// number of increments.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `scale`: usize - the coarse-graining scale
+/// # Returns
+/// * IrreversibilityIndices - the indices, NaN without increments
+pub fn irreversibility(series: &RRSeries, scale: usize) -> IrreversibilityIndices {
+    let size = series.rr.len().min(series.annot.len());
+    let values = coarse_grain(&series.rr[..size], scale);
+    let annotations = coarse_grain_annotations(&series.annot[..size], scale);
+    let classify = RunsConfig::default();
+    let (mut increments, mut acc, mut dec) = (0, 0, 0);
+    let (mut squares, mut dec_squares) = (0.0, 0.0);
+    for i in 1..values.len() {
+        if annotations[i - 1] != 0 || annotations[i] != 0 {
+            continue;
+        }
+        let Some(kind) = classify.classify(values[i - 1], values[i]) else {
+            continue;
+        };
+        let square = (values[i] - values[i - 1]).powi(2);
+        increments += 1;
+        squares += square;
+        match kind {
+            RunType::Acc => acc += 1,
+            RunType::Dec => {
+                dec += 1;
+                dec_squares += square;
+            }
+            RunType::Neu => {}
+        }
+    }
+    IrreversibilityIndices {
+        scale,
+        increments,
+        asymmetry: (acc as f64 - dec as f64) / increments as f64,
+        porta: 100.0 * acc as f64 / (acc + dec) as f64,
+        guzik: 100.0 * dec_squares / squares,
+    }
+}
+
+/// Calculate Irreversibility Indices at Scales 1..=max_scale
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `max_scale`: usize - the largest coarse-graining scale
+/// # Returns
+/// * MultiscaleIrreversibility - the indices per scale and their sums
+pub fn multiscale_irreversibility(
+    series: &RRSeries,
+    max_scale: usize,
+) -> MultiscaleIrreversibility {
+    let scales: Vec<IrreversibilityIndices> = (1..=max_scale)
+        .map(|scale| irreversibility(series, scale))
+        .collect();
+    let sum = |index: fn(&IrreversibilityIndices) -> f64| {
+        scales
+            .iter()
+            .map(index)
+            .filter(|value| !value.is_nan())
+            .sum()
+    };
+    MultiscaleIrreversibility {
+        asymmetry_index: sum(|s| s.asymmetry),
+        porta_index: sum(|s| s.porta - 50.0),
+        guzik_index: sum(|s| s.guzik - 50.0),
+        scales,
+    }
+}
+
+// surrogates with the normal beats shuffled among themselves
+fn shuffled_surrogates(series: &RRSeries, surrogates: usize, seed: u64) -> Vec<Vec<f64>> {
+    let normal: Vec<usize> = (0..series.rr.len().min(series.annot.len()))
+        .filter(|&i| series.annot[i] == 0)
+        .collect();
+    let values: Vec<f64> = normal.iter().map(|&i| series.rr[i]).collect();
+    let config = SurrogateConfig {
+        method: SurrogateMethod::Shuffle,
+        surrogates,
+        seed,
+    };
+    generate_surrogates(&values, &config)
+        .into_iter()
+        .map(|shuffled| {
+            let mut rr = series.rr.clone();
+            for (&i, value) in normal.iter().zip(shuffled) {
+                rr[i] = value;
+            }
+            rr
+        })
+        .collect()
+}
+
+// surrogate with the sign of every increment between normal beats flipped with probability 1/2
+fn sign_flipped_surrogate(series: &RRSeries, rng: &mut Rng) -> Vec<f64> {
+    let mut rr = series.rr.clone();
+    for i in 1..series.rr.len().min(series.annot.len()) {
+        if series.annot[i - 1] != 0 || series.annot[i] != 0 {
+            continue;
+        }
+        let increment = series.rr[i] - series.rr[i - 1];
+        let sign = if rng.next_u64() & 1 == 0 { 1.0 } else { -1.0 };
+        rr[i] = rr[i - 1] + sign * increment;
+    }
+    rr
+}
+
+/// Test Time Irreversibility with Surrogates
+///
+/// This function compares the multiscale asymmetry, Porta and Guzik indices of the
+/// series with their distributions over seeded surrogates of the chosen null
+/// hypothesis, which are time-reversible by construction, or with the indices of
+/// the time-reversed series.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `max_scale`: usize - the largest coarse-graining scale
+/// * `null`: IrreversibilityNull - the kind, number and seed of the surrogates
+/// # Returns
+/// * IrreversibilityTest - z-scores and rank p-values of the three multiscale indices, or their
+///   values for the reversed series
+pub fn test_irreversibility(
+    series: &RRSeries,
+    max_scale: usize,
+    null: IrreversibilityNull,
+) -> IrreversibilityTest {
+    let observed = multiscale_irreversibility(series, max_scale);
+    let with_rr = |rr: Vec<f64>, annot: Vec<u8>| RRSeries {
+        size: rr.len(),
+        rr,
+        annot,
+        column_names: series.column_names.clone(),
+    };
+    let surrogates: Vec<Vec<f64>> = match null {
+        IrreversibilityNull::Shuffled { surrogates, seed } => {
+            shuffled_surrogates(series, surrogates, seed)
+        }
+        IrreversibilityNull::SignFlipped { surrogates, seed } => {
+            let mut rng = Rng::new(seed);
+            (0..surrogates)
+                .map(|_| sign_flipped_surrogate(series, &mut rng))
+                .collect()
+        }
+        IrreversibilityNull::TimeReversed => {
+            let size = series.rr.len().min(series.annot.len());
+            let reversed = with_rr(
+                series.rr[..size].iter().rev().copied().collect(),
+                series.annot[..size].iter().rev().copied().collect(),
+            );
+            let reversed = multiscale_irreversibility(&reversed, max_scale);
+            return IrreversibilityTest {
+                null,
+                asymmetry_index: compare_reversed(
+                    observed.asymmetry_index,
+                    reversed.asymmetry_index,
+                ),
+                porta_index: compare_reversed(observed.porta_index, reversed.porta_index),
+                guzik_index: compare_reversed(observed.guzik_index, reversed.guzik_index),
+            };
+        }
+    };
+    let samples: Vec<MultiscaleIrreversibility> = surrogates
+        .into_iter()
+        .map(|rr| multiscale_irreversibility(&with_rr(rr, series.annot.clone()), max_scale))
+        .collect();
+    let values = |index: fn(&MultiscaleIrreversibility) -> f64| -> Vec<f64> {
+        samples.iter().map(index).collect()
+    };
+    IrreversibilityTest {
+        null,
+        asymmetry_index: compare(observed.asymmetry_index, &values(|s| s.asymmetry_index)),
+        porta_index: compare(observed.porta_index, &values(|s| s.porta_index)),
+        guzik_index: compare(observed.guzik_index, &values(|s| s.guzik_index)),
+    }
+}
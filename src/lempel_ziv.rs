@@ -0,0 +1,156 @@
+use crate::coarse_grain::coarse_grain;
+use crate::runs::{RunType, RunsConfig};
+
+/// Binarisation of a Series Before the Complexity Analysis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binarization {
+    /// 1 for values above the median, 0 otherwise
+    Median,
+    /// 1 for decelerations, 0 for accelerations and neutral steps, classified
+    /// like the steps of the runs analysis with the given settings
+    DifferenceSign(RunsConfig),
+}
+
+/// Lempel-Ziv Complexity of One Binary Sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct LempelZiv {
+    /// the coarse-graining scale, 1 for the original series
+    pub scale: usize,
+    /// the length of the binary sequence
+    pub n: usize,
+    /// the number of phrases of the LZ76 (Kaspar-Schuster) parsing
+    pub lz76: usize,
+    /// the number of phrases of the LZ78 parsing
+    pub lz78: usize,
+    /// lz76 * log2(n) / n, about 1 for a random sequence
+    pub lz76_normalized: f64,
+    /// lz78 * log2(n) / n
+    pub lz78_normalized: f64,
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+    if n == 0 {
+        f64::NAN
+    } else if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    }
+}
+
+/// Binarise a Series
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `coding`: Binarization - median or difference-sign coding
+/// # Returns
+/// * Vec<u8> - the binary sequence, one symbol per value (median) or per step (difference sign)
+pub fn binarize(signal: &[f64], coding: Binarization) -> Vec<u8> {
+    match coding {
+        Binarization::Median => {
+            let median = median(signal);
+            signal.iter().map(|&x| u8::from(x > median)).collect()
+        }
+        Binarization::DifferenceSign(config) => config
+            .step_types(signal)
+            .into_iter()
+            .map(|step| u8::from(step == Some(RunType::Dec)))
+            .collect(),
+    }
+}
+
+// number of phrases of the LZ76 parsing (Kaspar and Schuster 1987)
+fn lz76(s: &[u8]) -> usize {
+    let n = s.len();
+    if n < 2 {
+        return n;
+    }
+    let (mut c, mut l, mut i, mut k, mut k_max) = (1, 1, 0, 1, 1);
+    loop {
+        if s[i + k - 1] == s[l + k - 1] {
+            k += 1;
+            if l + k > n {
+                c += 1;
+                break;
+            }
+        } else {
+            k_max = k_max.max(k);
+            i += 1;
+            if i == l {
+                c += 1;
+                l += k_max;
+                if l + 1 > n {
+                    break;
+                }
+                i = 0;
+                k = 1;
+                k_max = 1;
+            } else {
+                k = 1;
+            }
+        }
+    }
+    c
+}
+
+// number of phrases of the LZ78 parsing - every phrase is the longest known phrase extended by
+// one symbol; an incomplete last phrase counts as well
+fn lz78(s: &[u8]) -> usize {
+    let mut phrases = std::collections::HashSet::new();
+    let mut start = 0;
+    for end in 1..=s.len() {
+        if phrases.insert(&s[start..end]) {
+            start = end;
+        }
+    }
+    phrases.len() + usize::from(start < s.len())
+}
+
+/// Calculate Lempel-Ziv Complexity
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `coding`: Binarization - the binarisation of the data
+/// # Returns
+/// * LempelZiv - the LZ76 and LZ78 phrase counts, raw and normalised
+pub fn calc_lempel_ziv(signal: &[f64], coding: Binarization) -> LempelZiv {
+    let symbols = binarize(signal, coding);
+    let n = symbols.len();
+    let (lz76, lz78) = (lz76(&symbols), lz78(&symbols));
+    let normalization = (n as f64).log2() / n as f64;
+    LempelZiv {
+        scale: 1,
+        n,
+        lz76,
+        lz78,
+        lz76_normalized: lz76 as f64 * normalization,
+        lz78_normalized: lz78 as f64 * normalization,
+    }
+}
+
+/// Calculate Multiscale Lempel-Ziv Complexity
+///
+/// This function coarse-grains the series like multiscale entropy at scales
+/// 1..=max_scale and calculates the Lempel-Ziv complexity at every scale.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `coding`: Binarization - the binarisation of the coarse-grained data
+/// * `max_scale`: usize - the largest scale
+/// # Returns
+/// * Vec<LempelZiv> - the complexity at every scale
+pub fn calc_multiscale_lempel_ziv(
+    signal: &[f64],
+    coding: Binarization,
+    max_scale: usize,
+) -> Vec<LempelZiv> {
+    (1..=max_scale)
+        .map(|scale| LempelZiv {
+            scale,
+            ..calc_lempel_ziv(&coarse_grain(signal, scale), coding)
+        })
+        .collect()
+}
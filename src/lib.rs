@@ -1,4 +1,18 @@
 // lib.rs
+pub mod af_detection; // module for atrial fibrillation screening
+pub mod baroreflex; // module for baroreflex sensitivity
+pub mod chaos; // module for correlation dimension and Lyapunov exponents
+pub mod coarse_grain; // coarse-graining for multiscale analyses
 pub mod data_reader; // module for common data handling
+pub mod fractal; // module for fractal measures
+pub mod hrt; // module for heart rate turbulence
+pub mod irreversibility; // module for time irreversibility
+pub mod lempel_ziv; // module for Lempel-Ziv complexity
+pub mod prsa; // module for phase-rectified signal averaging
+mod rng; // seeded random numbers for surrogate data
+pub mod rqa; // module for recurrence quantification analysis
 pub mod runs; // module for runs analysis
 pub mod samp_en; // module for entropy analysis
+mod stats; // helper statistics shared by the analysis modules
+pub mod surrogates; // module for surrogate data testing
+pub mod symbolic; // module for symbolic dynamics
@@ -0,0 +1,110 @@
+use crate::data_reader::RRSeries;
+use crate::runs::{RunType, RunsConfig};
+
+/// Settings of Phase-Rectified Signal Averaging
+///
+/// The defaults follow Bauer et al. (2006): anchors are all decelerations or
+/// accelerations whose RR change does not exceed 5% of the preceding interval,
+/// and the curve spans L = 30 beats on each side of the anchor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrsaConfig {
+    /// the window half-length L in beats; the curve covers anchor - L..anchor + L
+    pub window: usize,
+    /// anchors with a larger RR change, as a fraction of the preceding interval, are ignored
+    pub max_change: f64,
+}
+
+impl Default for PrsaConfig {
+    fn default() -> Self {
+        PrsaConfig {
+            window: 30,
+            max_change: 0.05,
+        }
+    }
+}
+
+/// One Phase-Rectified Curve
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrsaCurve {
+    /// Dec for deceleration anchors, Acc for acceleration anchors
+    pub kind: RunType,
+    /// the number of anchors averaged
+    pub anchors: usize,
+    /// the averaged RR intervals X(-L)..X(L - 1), the anchor X(0) at index L; empty for L = 0
+    pub curve: Vec<f64>,
+    /// (X(0) + X(1) - X(-1) - X(-2)) / 4, NaN without anchors or for L < 2
+    pub capacity: f64,
+}
+
+/// Result of Phase-Rectified Signal Averaging
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prsa {
+    /// the deceleration curve, its capacity is DC
+    pub dc: PrsaCurve,
+    /// the acceleration curve, its capacity is AC
+    pub ac: PrsaCurve,
+}
+
+// averaging the windows around all anchors of one kind
+fn prsa_curve(series: &RRSeries, kind: RunType, config: &PrsaConfig) -> PrsaCurve {
+    let rr = &series.rr;
+    let size = rr.len().min(series.annot.len());
+    let l = config.window;
+    let classify = RunsConfig::default();
+    let mut curve = vec![0.0; 2 * l];
+    let mut anchors = 0;
+    // every anchor needs its preceding beat, also for L = 0
+    let margin = l.max(1);
+    for i in margin..(size + 1).saturating_sub(margin) {
+        if classify.classify(rr[i - 1], rr[i]) != Some(kind)
+            || (rr[i] - rr[i - 1]).abs() > config.max_change * rr[i - 1]
+        {
+            continue;
+        }
+        // anchors with an annotated beat in their window (or the preceding beat) are skipped
+        if series.annot[(i - 1).min(i - l)..i + l]
+            .iter()
+            .any(|&a| a != 0)
+        {
+            continue;
+        }
+        for (x, value) in curve.iter_mut().zip(&rr[i - l..i + l]) {
+            *x += value;
+        }
+        anchors += 1;
+    }
+    for x in curve.iter_mut() {
+        *x /= anchors as f64;
+    }
+    let capacity = if l >= 2 && anchors > 0 {
+        (curve[l] + curve[l + 1] - curve[l - 1] - curve[l - 2]) / 4.0
+    } else {
+        f64::NAN
+    };
+    PrsaCurve {
+        kind,
+        anchors,
+        curve,
+        capacity,
+    }
+}
+
+/// Calculate Deceleration and Acceleration Capacity
+///
+/// This function implements phase-rectified signal averaging (Bauer et al. 2006).
+/// Anchors are the beats whose RR interval is longer (decelerations) or shorter
+/// (accelerations) than the preceding one, classified like the steps of the runs
+/// analysis; the windows of 2L beats around the anchors are averaged, and DC and AC
+/// are the Haar wavelet coefficients at the anchor.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `config`: &PrsaConfig - the window length and the anchor criteria
+/// # Returns
+/// * Prsa - the deceleration and acceleration curves with DC and AC
+pub fn calc_prsa(series: &RRSeries, config: &PrsaConfig) -> Prsa {
+    Prsa {
+        dc: prsa_curve(series, RunType::Dec, config),
+        ac: prsa_curve(series, RunType::Acc, config),
+    }
+}
@@ -0,0 +1,268 @@
+use crate::data_reader::RRSeries;
+use crate::samp_en::{select_tolerance, Tolerance};
+use std::collections::BTreeMap;
+
+/// Settings of Recurrence Quantification Analysis
+///
+/// The embedding dimension 10, delay 1 and lines of at least 2 points follow the
+/// RQA of Kubios HRV. Recurrence uses the max norm of the template matching of
+/// sample entropy rather than the Euclidean norm of Kubios, so its r = √10 SD would
+/// mark almost every pair as recurrent; the default r = 0.2 SD is the usual radius
+/// of sample entropy instead, which keeps the matrix sparse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RqaConfig {
+    /// the embedding dimension
+    pub m: usize,
+    /// the embedding delay in beats
+    pub tau: usize,
+    /// the radius of comparison, resolved with samp_en::select_tolerance on the normal beats
+    pub tolerance: Tolerance,
+    /// the minimal length of diagonal lines
+    pub l_min: usize,
+    /// the minimal length of vertical lines
+    pub v_min: usize,
+    /// points with |i - j| below this window are left out; 1 leaves out the line of identity
+    pub theiler: usize,
+}
+
+impl Default for RqaConfig {
+    fn default() -> Self {
+        RqaConfig {
+            m: 10,
+            tau: 1,
+            tolerance: Tolerance::SdFraction(0.2),
+            l_min: 2,
+            v_min: 2,
+            theiler: 1,
+        }
+    }
+}
+
+/// Sparse Recurrence Matrix
+///
+/// Only the recurrent points are stored, row by row with sorted column indices,
+/// so the memory grows with the number of recurrences rather than with N².
+/// Points within the Theiler window and embedding vectors containing an annotated
+/// beat are never recurrent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceMatrix {
+    size: usize,
+    r: f64,
+    theiler: usize,
+    offsets: Vec<usize>,
+    columns: Vec<usize>,
+}
+
+impl RecurrenceMatrix {
+    /// Build the Recurrence Matrix of a Time-Delay Embedded Series
+    ///
+    /// # Parameters
+    /// * `series`: &RRSeries - the RR intervals with their annotations
+    /// * `m`: usize - the embedding dimension
+    /// * `tau`: usize - the embedding delay
+    /// * `r`: f64 - the radius of comparison; vectors are recurrent if their max-norm distance is <= r
+    /// * `theiler`: usize - the Theiler window
+    /// # Returns
+    /// * RecurrenceMatrix - the sparse matrix of the N - (m - 1) tau embedding vectors
+    pub fn new(series: &RRSeries, m: usize, tau: usize, r: f64, theiler: usize) -> Self {
+        let length = series.rr.len().min(series.annot.len());
+        let span = m.max(1).saturating_sub(1) * tau;
+        let size = length.saturating_sub(span);
+        let valid: Vec<bool> = (0..size)
+            .map(|i| (0..m.max(1)).all(|k| series.annot[i + k * tau] == 0))
+            .collect();
+        let rr = &series.rr;
+        // candidates are found by sorting on the first coordinate, which has to be within r as well
+        let mut order: Vec<usize> = (0..size).filter(|&i| valid[i]).collect();
+        order.sort_by(|&a, &b| rr[a].total_cmp(&rr[b]));
+        let mut rows: Vec<Vec<usize>> = vec![Vec::new(); size];
+        for (position, &i) in order.iter().enumerate() {
+            for &j in order[position + 1..]
+                .iter()
+                .take_while(|&&j| rr[j] - rr[i] <= r)
+            {
+                if i.abs_diff(j) < theiler {
+                    continue;
+                }
+                let recurrent = (1..m).all(|k| (rr[i + k * tau] - rr[j + k * tau]).abs() <= r);
+                if recurrent {
+                    rows[i].push(j);
+                    rows[j].push(i);
+                }
+            }
+            if theiler == 0 {
+                rows[i].push(i);
+            }
+        }
+        let mut offsets = Vec::with_capacity(size + 1);
+        let mut columns = Vec::new();
+        offsets.push(0);
+        for mut row in rows {
+            row.sort_unstable();
+            columns.extend(row);
+            offsets.push(columns.len());
+        }
+        RecurrenceMatrix {
+            size,
+            r,
+            theiler,
+            offsets,
+            columns,
+        }
+    }
+
+    /// the number of embedding vectors, i.e. of rows and columns
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// the radius of comparison used
+    pub fn r(&self) -> f64 {
+        self.r
+    }
+
+    /// the number of recurrent points
+    pub fn recurrences(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// the sorted columns of the recurrent points in row i
+    pub fn row(&self, i: usize) -> &[usize] {
+        &self.columns[self.offsets[i]..self.offsets[i + 1]]
+    }
+
+    pub fn is_recurrent(&self, i: usize, j: usize) -> bool {
+        i < self.size && j < self.size && self.row(i).binary_search(&j).is_ok()
+    }
+}
+
+/// Recurrence Quantification Measures
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rqa {
+    /// the radius of comparison used
+    pub r: f64,
+    /// recurrence rate - recurrent points divided by the points outside the Theiler window
+    pub recurrence_rate: f64,
+    /// determinism - the fraction of recurrent points on diagonal lines of at least l_min points
+    pub determinism: f64,
+    /// the mean length of diagonal lines of at least l_min points
+    pub mean_line: f64,
+    /// the length of the longest diagonal line
+    pub max_line: usize,
+    /// Shannon entropy (natural log) of the lengths of diagonal lines of at least l_min points
+    pub entropy: f64,
+    /// laminarity - the fraction of recurrent points on vertical lines of at least v_min points
+    pub laminarity: f64,
+    /// trapping time - the mean length of vertical lines of at least v_min points
+    pub trapping_time: f64,
+    /// numbers of diagonal lines by length, counted in the upper triangle
+    pub diagonal_lines: BTreeMap<usize, usize>,
+    /// numbers of vertical lines by length
+    pub vertical_lines: BTreeMap<usize, usize>,
+}
+
+// fraction of points on lines of at least `min` points, their mean length and the entropy of their lengths
+fn line_statistics(lines: &BTreeMap<usize, usize>, min: usize) -> (f64, f64, f64) {
+    let points: usize = lines.iter().map(|(l, n)| l * n).sum();
+    let long = lines.range(min.max(1)..);
+    let long_points: usize = long.clone().map(|(l, n)| l * n).sum();
+    let long_lines: usize = long.clone().map(|(_, n)| n).sum();
+    let entropy = -long
+        .map(|(_, &n)| {
+            let p = n as f64 / long_lines as f64;
+            p * p.ln()
+        })
+        .sum::<f64>();
+    (
+        long_points as f64 / points as f64,
+        long_points as f64 / long_lines as f64,
+        entropy,
+    )
+}
+
+impl RecurrenceMatrix {
+    // numbers of diagonal lines in the upper triangle by length
+    fn diagonal_lines(&self) -> BTreeMap<usize, usize> {
+        let mut lines = BTreeMap::new();
+        for i in 0..self.size {
+            for &j in self.row(i).iter().filter(|&&j| j > i) {
+                // only lines starting at (i, j) are followed
+                if i > 0 && self.is_recurrent(i - 1, j - 1) {
+                    continue;
+                }
+                let mut length = 1;
+                while self.is_recurrent(i + length, j + length) {
+                    length += 1;
+                }
+                *lines.entry(length).or_insert(0) += 1;
+            }
+        }
+        lines
+    }
+
+    // numbers of vertical lines by length - the matrix is symmetric, so these are the runs of consecutive columns in the rows
+    fn vertical_lines(&self) -> BTreeMap<usize, usize> {
+        let mut lines = BTreeMap::new();
+        for i in 0..self.size {
+            let row = self.row(i);
+            let mut length = 0;
+            for (k, &j) in row.iter().enumerate() {
+                length += 1;
+                if k + 1 == row.len() || row[k + 1] != j + 1 {
+                    *lines.entry(length).or_insert(0) += 1;
+                    length = 0;
+                }
+            }
+        }
+        lines
+    }
+
+    /// Calculate the Recurrence Quantification Measures
+    ///
+    /// # Parameters
+    /// * `l_min`: usize - the minimal length of diagonal lines
+    /// * `v_min`: usize - the minimal length of vertical lines
+    /// # Returns
+    /// * Rqa - RR, DET, L, Lmax, ENTR, LAM and TT
+    pub fn quantify(&self, l_min: usize, v_min: usize) -> Rqa {
+        let n = self.size;
+        // points within the Theiler window
+        let excluded: usize = (0..self.theiler.min(n))
+            .map(|d| if d == 0 { n } else { 2 * (n - d) })
+            .sum();
+        let diagonal_lines = self.diagonal_lines();
+        let vertical_lines = self.vertical_lines();
+        let (determinism, mean_line, entropy) = line_statistics(&diagonal_lines, l_min);
+        let (laminarity, trapping_time, _) = line_statistics(&vertical_lines, v_min);
+        Rqa {
+            r: self.r,
+            recurrence_rate: self.recurrences() as f64 / (n * n - excluded) as f64,
+            determinism,
+            mean_line,
+            max_line: diagonal_lines.keys().max().copied().unwrap_or(0),
+            entropy,
+            laminarity,
+            trapping_time,
+            diagonal_lines,
+            vertical_lines,
+        }
+    }
+}
+
+/// Recurrence Quantification Analysis of an RR Series
+///
+/// This function embeds the RR intervals with dimension m and delay tau, marks
+/// pairs of embedding vectors within the max-norm distance r as recurrent, like
+/// the template matching of sample entropy, and quantifies the diagonal and
+/// vertical line structures of the sparse recurrence matrix.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `config`: &RqaConfig - the embedding, tolerance and line settings
+/// # Returns
+/// * Rqa - RR, DET, L, Lmax, ENTR, LAM and TT with the line length histograms
+pub fn calc_rqa(series: &RRSeries, config: &RqaConfig) -> Rqa {
+    let r = select_tolerance(&series.normal_rr(), config.m, config.tolerance);
+    RecurrenceMatrix::new(series, config.m, config.tau, r, config.theiler)
+        .quantify(config.l_min, config.v_min)
+}
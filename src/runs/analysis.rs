@@ -0,0 +1,60 @@
+use super::stream::{RunEvent, RunsTracker};
+use super::{NeutralTolerance, RunType, RunValue, RunsConfig, SeriesRuns, TruncatedRuns};
+use std::cmp::Ordering;
+
+impl RunsConfig {
+    // classifying the change from one value to the next, None if the values cannot be compared (NaN)
+    pub(crate) fn classify<T: RunValue>(&self, previous: T, next: T) -> Option<RunType> {
+        let ordering = next.partial_cmp(&previous)?;
+        let change = next.to_f64() - previous.to_f64();
+        let neutral = match self.neutral {
+            NeutralTolerance::Exact => ordering == Ordering::Equal,
+            NeutralTolerance::Absolute(tolerance) => change.abs() <= tolerance,
+            NeutralTolerance::Relative(tolerance) => {
+                change.abs() <= tolerance * previous.to_f64().abs()
+            }
+        };
+        Some(if neutral {
+            RunType::Neu
+        } else if ordering == Ordering::Greater {
+            RunType::Dec
+        } else {
+            RunType::Acc
+        })
+    }
+
+    // classifying every step of a series, i.e. its acceleration/deceleration coding;
+    // step i joins values i and i + 1, None where they cannot be compared
+    pub fn step_types<T: RunValue>(&self, values: &[T]) -> Vec<Option<RunType>> {
+        values
+            .windows(2)
+            .map(|pair| self.classify(pair[0], pair[1]))
+            .collect()
+    }
+}
+
+impl<T: RunValue> SeriesRuns<T> {
+    // storing a completed run according to its boundary status
+    fn record(&mut self, event: Option<RunEvent>) {
+        if let Some(RunEvent { run, status }) = event {
+            match status {
+                TruncatedRuns::Count => self.accumulator.add_run(run),
+                TruncatedRuns::Censor => self.censored.add_run(run),
+                TruncatedRuns::Drop => {}
+            }
+        }
+    }
+
+    // analyzing runs by feeding all beats to the runs state machine
+    pub(crate) fn analyze_runs(&mut self) {
+        let mut tracker = RunsTracker::new(self.config);
+        let size = self.rr_intervals.len().min(self.annotations.len());
+        for i in 0..size {
+            let event = tracker.push(self.rr_intervals[i], self.annotations[i]);
+            self.record(event);
+        }
+        self.record(tracker.finish());
+        self.set_max();
+        self.analyzed = true;
+    }
+}
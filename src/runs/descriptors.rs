@@ -0,0 +1,92 @@
+use super::{RunType, RunValue, RunsAccumulator, SeriesRuns};
+
+// descriptors of the run-length distribution of one run type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunTypeDescriptors {
+    pub runs: i32,          // number of runs
+    pub beats: usize,       // number of beats in the runs, i.e. the sum of their lengths
+    pub entropy: f64,       // Shannon entropy (natural log) of the run-length distribution
+    pub mean_length: f64,   // NaN if there are no runs
+    pub longest: usize,     // 0 if there are no runs
+    pub long_fraction: f64, // beats in runs of length >= k divided by the beats in all runs of all types
+}
+
+// descriptors of the run-length distributions, derived from the numbers of runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunsDescriptors {
+    pub k: usize, // the minimal run length counted in long_fraction
+    pub dec: RunTypeDescriptors,
+    pub acc: RunTypeDescriptors,
+    pub neu: RunTypeDescriptors,
+    pub long_fraction: f64, // beats in runs of length >= k of any type divided by the beats in all runs
+    // numbers of deceleration runs divided by numbers of acceleration runs for lengths 1..=max
+    // (index 0 - length 1); infinite if there are only deceleration runs, NaN if there are none
+    pub asymmetry: Vec<f64>,
+}
+
+impl RunsAccumulator {
+    fn type_descriptors(&self, kind: RunType, k: usize, all_beats: usize) -> RunTypeDescriptors {
+        let runs = self.total(kind);
+        let beats: usize = self
+            .counts(kind)
+            .iter()
+            .map(|(&length, &count)| length * count as usize)
+            .sum();
+        let long_beats: usize = self
+            .counts(kind)
+            .iter()
+            .filter(|(&length, _)| length >= k)
+            .map(|(&length, &count)| length * count as usize)
+            .sum();
+        let entropy = -self
+            .counts(kind)
+            .values()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / runs as f64;
+                p * p.ln()
+            })
+            .sum::<f64>();
+        RunTypeDescriptors {
+            runs,
+            beats,
+            entropy,
+            mean_length: beats as f64 / runs as f64,
+            longest: self.max_length(kind),
+            long_fraction: long_beats as f64 / all_beats as f64,
+        }
+    }
+
+    // calculating the entropies, mean and longest runs, the fraction of beats in runs
+    // of length >= k and the deceleration/acceleration asymmetry per length
+    pub fn descriptors(&self, k: usize) -> RunsDescriptors {
+        let all_beats: usize = [RunType::Dec, RunType::Acc, RunType::Neu]
+            .iter()
+            .flat_map(|&kind| self.counts(kind).iter())
+            .map(|(&length, &count)| length * count as usize)
+            .sum();
+        let dec = self.type_descriptors(RunType::Dec, k, all_beats);
+        let acc = self.type_descriptors(RunType::Acc, k, all_beats);
+        let neu = self.type_descriptors(RunType::Neu, k, all_beats);
+        let asymmetry = (1..=dec.longest.max(acc.longest))
+            .map(|length| {
+                self.count(RunType::Dec, length) as f64 / self.count(RunType::Acc, length) as f64
+            })
+            .collect();
+        RunsDescriptors {
+            k,
+            long_fraction: dec.long_fraction + acc.long_fraction + neu.long_fraction,
+            dec,
+            acc,
+            neu,
+            asymmetry,
+        }
+    }
+}
+
+impl<T: RunValue> SeriesRuns<T> {
+    // getting the descriptors of the counted runs
+    pub fn descriptors(&mut self, k: usize) -> RunsDescriptors {
+        self.get_full_runs().descriptors(k)
+    }
+}
@@ -0,0 +1,70 @@
+use super::{BoundaryPolicy, Run, RunType, RunValue, RunsConfig, SeriesRuns};
+
+// heart rate fragmentation indices (Costa et al. 2017) - percentages are of the RR changes
+// (steps) between normal beats, the acceleration/deceleration segments are the runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fragmentation {
+    pub steps: usize,       // number of RR changes between normal beats
+    pub inflections: usize, // number of beats where one run ends and the next begins
+    pub pip: f64,           // percentage of inflection points
+    pub ials: f64,          // inverse of the mean run length
+    pub pss: f64,           // percentage of steps in runs shorter than 3
+    pub pas: f64,           // percentage of steps in alternations of at least 4 runs of length 1
+}
+
+impl<T: RunValue> SeriesRuns<T> {
+    // calculating the fragmentation indices; steps are classified with the neutral settings of
+    // this instance, so a neutral step is a segment of its own unless neutral_continues_run is set,
+    // and the truncated runs are always used
+    pub fn fragmentation(&self) -> Fragmentation {
+        let config = RunsConfig {
+            boundaries: BoundaryPolicy::default(),
+            ..self.config
+        };
+        let mut all = SeriesRuns::from_values(
+            self.rr_intervals.clone(),
+            Some(self.annotations.clone()),
+            config,
+        );
+        let runs: Vec<Run> = all.runs().map(|run| *run.run()).collect();
+        let steps: usize = runs.iter().map(|run| run.length).sum();
+        let inflections = runs
+            .windows(2)
+            .filter(|pair| pair[0].end == pair[1].start)
+            .count();
+        let short: usize = runs
+            .iter()
+            .filter(|run| run.length < 3)
+            .map(|run| run.length)
+            .sum();
+
+        // alternations - chains of adjacent acceleration and deceleration runs of length 1
+        let mut alternating = 0;
+        let mut chain = 0;
+        for (i, run) in runs.iter().enumerate() {
+            let single = run.length == 1 && run.kind != RunType::Neu;
+            let continues = i > 0 && runs[i - 1].end == run.start;
+            if single && continues && chain > 0 {
+                chain += 1;
+            } else {
+                if chain >= 4 {
+                    alternating += chain;
+                }
+                chain = usize::from(single);
+            }
+        }
+        if chain >= 4 {
+            alternating += chain;
+        }
+
+        let percentage = |count: usize| 100.0 * count as f64 / steps as f64;
+        Fragmentation {
+            steps,
+            inflections,
+            pip: percentage(inflections),
+            ials: runs.len() as f64 / steps as f64,
+            pss: percentage(short),
+            pas: percentage(alternating),
+        }
+    }
+}
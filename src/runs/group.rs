@@ -0,0 +1,152 @@
+use super::{RunType, RunsAccumulator};
+use crate::stats::{mean, std_dev};
+use std::ops::{Add, AddAssign};
+
+impl RunsAccumulator {
+    // adding the runs of another accumulator, e.g. of the next segment of the same recording;
+    // merged run records keep the indices of the series they were found in
+    pub fn merge(&mut self, other: &RunsAccumulator) {
+        for kind in [RunType::Dec, RunType::Acc, RunType::Neu] {
+            for (&length, &count) in other.counts(kind) {
+                *self.counts_mut(kind).entry(length).or_insert(0) += count;
+            }
+        }
+        self.runs.extend_from_slice(&other.runs);
+    }
+
+    // getting the number of runs of a given type and length divided by the number of all runs
+    pub fn proportion(&self, kind: RunType, length: usize) -> f64 {
+        let all = self.total(RunType::Dec) + self.total(RunType::Acc) + self.total(RunType::Neu);
+        self.count(kind, length) as f64 / all as f64
+    }
+}
+
+impl AddAssign<&RunsAccumulator> for RunsAccumulator {
+    fn add_assign(&mut self, other: &RunsAccumulator) {
+        self.merge(other);
+    }
+}
+
+impl Add for RunsAccumulator {
+    type Output = RunsAccumulator;
+
+    fn add(mut self, other: RunsAccumulator) -> RunsAccumulator {
+        self.merge(&other);
+        self
+    }
+}
+
+// how the counts of each subject enter the group statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    Counts,      // raw numbers of runs
+    Proportions, // numbers of runs divided by the number of all runs of the subject
+}
+
+// group statistics for one run length
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSummaryRow {
+    pub length: usize,
+    pub mean_acc: f64,
+    pub sd_acc: f64,
+    pub mean_dec: f64,
+    pub sd_dec: f64,
+    pub mean_neu: f64,
+    pub sd_neu: f64,
+    // proportions of subjects having at least one run of this length or longer
+    pub subjects_acc: f64,
+    pub subjects_dec: f64,
+    pub subjects_neu: f64,
+}
+
+// group statistics for run lengths 1..=max over all subjects
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSummary {
+    pub subjects: usize,
+    pub normalization: Normalization,
+    pub rows: Vec<GroupSummaryRow>,
+}
+
+// runs accumulators of many subjects, each possibly merged from several segments
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunsGroup {
+    subjects: Vec<RunsAccumulator>,
+}
+
+impl RunsGroup {
+    pub fn new() -> Self {
+        RunsGroup::default()
+    }
+
+    pub fn add_subject(&mut self, accumulator: RunsAccumulator) {
+        self.subjects.push(accumulator);
+    }
+
+    pub fn subjects(&self) -> &[RunsAccumulator] {
+        &self.subjects
+    }
+
+    // pooling the runs of all subjects into one accumulator
+    pub fn pooled(&self) -> RunsAccumulator {
+        let mut pooled = RunsAccumulator::new();
+        for subject in &self.subjects {
+            pooled.merge(subject);
+        }
+        pooled
+    }
+
+    // calculating mean and SD (n - 1) over subjects of the numbers of runs per length
+    pub fn summary(&self, normalization: Normalization) -> GroupSummary {
+        let max_length = self
+            .subjects
+            .iter()
+            .flat_map(|s| [RunType::Dec, RunType::Acc, RunType::Neu].map(|kind| s.max_length(kind)))
+            .max()
+            .unwrap_or(0);
+        let value = |subject: &RunsAccumulator, kind, length| match normalization {
+            Normalization::Counts => subject.count(kind, length) as f64,
+            Normalization::Proportions => subject.proportion(kind, length),
+        };
+        let statistics = |kind, length| {
+            let values: Vec<f64> = self
+                .subjects
+                .iter()
+                .map(|subject| value(subject, kind, length))
+                .collect();
+            let having = self
+                .subjects
+                .iter()
+                .filter(|subject| subject.max_length(kind) >= length)
+                .count();
+            (
+                mean(&values),
+                std_dev(&values),
+                having as f64 / self.subjects.len() as f64,
+            )
+        };
+        let rows = (1..=max_length)
+            .map(|length| {
+                let (mean_acc, sd_acc, subjects_acc) = statistics(RunType::Acc, length);
+                let (mean_dec, sd_dec, subjects_dec) = statistics(RunType::Dec, length);
+                let (mean_neu, sd_neu, subjects_neu) = statistics(RunType::Neu, length);
+                GroupSummaryRow {
+                    length,
+                    mean_acc,
+                    sd_acc,
+                    mean_dec,
+                    sd_dec,
+                    mean_neu,
+                    sd_neu,
+                    subjects_acc,
+                    subjects_dec,
+                    subjects_neu,
+                }
+            })
+            .collect();
+        GroupSummary {
+            subjects: self.subjects.len(),
+            normalization,
+            rows,
+        }
+    }
+}
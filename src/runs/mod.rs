@@ -0,0 +1,359 @@
+mod analysis;
+mod descriptors;
+mod fragmentation;
+mod group;
+mod multiscale;
+mod output;
+mod report;
+mod significance;
+mod stream;
+mod summary;
+mod variance;
+mod windowed;
+
+pub use descriptors::{RunTypeDescriptors, RunsDescriptors};
+pub use fragmentation::Fragmentation;
+pub use group::{GroupSummary, GroupSummaryRow, Normalization, RunsGroup};
+pub use multiscale::ScaleRuns;
+pub use report::{RunsReport, RunsSummary, RunsSummaryRow, RunsTable};
+pub use significance::{
+    expected_iid_runs, Representation, RunLengthTest, RunsDistributionTest, RunsExpectation,
+};
+pub use stream::{RunEvent, RunsStream};
+pub use windowed::{WindowRuns, WindowSize};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+
+// values the runs engine can work on - anything ordered that can be converted to f64 for
+// tolerances and times, e.g. RR intervals in ms (f64, f32) or in samples (integers)
+pub trait RunValue: PartialOrd + Copy {
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_run_value {
+    ($($t:ty),*) => {
+        $(
+            impl RunValue for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_run_value!(f64, f32, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+// defining run types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunType {
+    Dec = 1,  // deceleration run
+    Neu = 0,  // neutral run
+    Acc = -1, // acceleration run
+}
+
+impl fmt::Display for RunType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RunType::Dec => "DEC",
+            RunType::Acc => "ACC",
+            RunType::Neu => "NEU",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// describing a single run - the run starts at the reference beat `start`, its beats
+// are start + 1..=end, and `length` (end - start) is the number of RR changes in the run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Run {
+    pub start: usize, // index of the reference beat
+    pub end: usize,   // index of the last beat of the run
+    pub length: usize,
+    pub kind: RunType,
+}
+
+// sum of the values converted to f64, i.e. the time they span if they are intervals
+fn total<T: RunValue>(values: &[T]) -> f64 {
+    values.iter().map(|&value| value.to_f64()).sum()
+}
+
+impl Run {
+    // returning the RR intervals of the run, reference beat included
+    pub fn rr_values<'a, T>(&self, rr: &'a [T]) -> &'a [T] {
+        &rr[self.start..=self.end]
+    }
+
+    // returning the time of the reference beat, counted from the start of the recording
+    pub fn onset<T: RunValue>(&self, rr: &[T]) -> f64 {
+        total(&rr[..=self.start])
+    }
+
+    // returning the time of the last beat of the run
+    pub fn offset<T: RunValue>(&self, rr: &[T]) -> f64 {
+        total(&rr[..=self.end])
+    }
+
+    // returning the time spanned by the run, i.e. the sum of its RR intervals without the reference beat
+    pub fn duration<T: RunValue>(&self, rr: &[T]) -> f64 {
+        total(&rr[(self.start + 1)..=self.end])
+    }
+}
+
+// a run together with the values it was found in, as yielded by SeriesRuns::runs
+#[derive(Debug, Clone, Copy)]
+pub struct RunView<'a, T = f64> {
+    run: &'a Run,
+    rr: &'a [T],
+}
+
+impl<'a, T: RunValue> RunView<'a, T> {
+    pub fn run(&self) -> &'a Run {
+        self.run
+    }
+
+    pub fn rr_values(&self) -> &'a [T] {
+        self.run.rr_values(self.rr)
+    }
+
+    pub fn onset(&self) -> f64 {
+        self.run.onset(self.rr)
+    }
+
+    pub fn offset(&self) -> f64 {
+        self.run.offset(self.rr)
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.run.duration(self.rr)
+    }
+}
+
+impl<T> Deref for RunView<'_, T> {
+    type Target = Run;
+
+    fn deref(&self) -> &Run {
+        self.run
+    }
+}
+
+// deciding when a change between consecutive RR intervals counts as neutral
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NeutralTolerance {
+    Exact,         // only identical RR intervals are neutral
+    Absolute(f64), // changes up to this value (in the units of the values, e.g. ms) are neutral
+    Relative(f64), // changes up to this fraction of the preceding value are neutral
+}
+
+// what happens to a run whose true beginning or end is unknown; when a run is truncated
+// on both sides, the stricter policy wins (Drop over Censor over Count)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TruncatedRuns {
+    Count,  // counted like any other run
+    Censor, // reported separately as censored
+    Drop,   // left out
+}
+
+// handling of runs truncated by the recording start, the recording end, or an annotated beat
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundaryPolicy {
+    pub start: TruncatedRuns, // the run beginning with the first beat of the recording
+    pub end: TruncatedRuns,   // the run ending with the last beat of the recording
+    pub artifact: TruncatedRuns, // runs directly after or before an annotated beat
+}
+
+impl Default for BoundaryPolicy {
+    fn default() -> Self {
+        BoundaryPolicy {
+            start: TruncatedRuns::Count,
+            end: TruncatedRuns::Count,
+            artifact: TruncatedRuns::Count,
+        }
+    }
+}
+
+// settings of the runs analysis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunsConfig {
+    pub boundaries: BoundaryPolicy,
+    pub neutral: NeutralTolerance,
+    // if true, neutral steps extend the current acceleration or deceleration run
+    // instead of starting a neutral run; neutral runs are then only found before
+    // the first acceleration or deceleration after the start or an annotated beat
+    pub neutral_continues_run: bool,
+}
+
+impl Default for RunsConfig {
+    fn default() -> Self {
+        RunsConfig {
+            boundaries: BoundaryPolicy::default(),
+            neutral: NeutralTolerance::Exact,
+            neutral_continues_run: false,
+        }
+    }
+}
+
+// storing run statistics and addresses
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunsAccumulator {
+    dec: HashMap<usize, i32>, // storing statistics for deceleration runs
+    acc: HashMap<usize, i32>, // storing statistics for acceleration runs
+    neu: HashMap<usize, i32>, // storing statistics for neutral runs
+    runs: Vec<Run>,           // storing addresses of runs in the order they were found
+}
+
+impl RunsAccumulator {
+    // creating an empty accumulator, e.g. as a starting point for merging
+    pub fn new() -> Self {
+        RunsAccumulator::default()
+    }
+
+    pub(crate) fn counts(&self, kind: RunType) -> &HashMap<usize, i32> {
+        match kind {
+            RunType::Dec => &self.dec,
+            RunType::Acc => &self.acc,
+            RunType::Neu => &self.neu,
+        }
+    }
+
+    pub(crate) fn counts_mut(&mut self, kind: RunType) -> &mut HashMap<usize, i32> {
+        match kind {
+            RunType::Dec => &mut self.dec,
+            RunType::Acc => &mut self.acc,
+            RunType::Neu => &mut self.neu,
+        }
+    }
+
+    // getting the number of runs of a given type and length
+    pub fn count(&self, kind: RunType, length: usize) -> i32 {
+        *self.counts(kind).get(&length).unwrap_or(&0)
+    }
+
+    // getting the number of all runs of a given type
+    pub fn total(&self, kind: RunType) -> i32 {
+        self.counts(kind).values().sum()
+    }
+
+    // getting the length of the longest run of a given type, 0 if there are none
+    pub fn max_length(&self, kind: RunType) -> usize {
+        self.counts(kind).keys().max().copied().unwrap_or(0)
+    }
+
+    // getting the runs in the order they were found
+    pub fn runs(&self) -> &[Run] {
+        &self.runs
+    }
+
+    // counting a run without keeping its address
+    pub(crate) fn add_count(&mut self, kind: RunType, length: usize) {
+        *self.counts_mut(kind).entry(length).or_insert(0) += 1;
+    }
+
+    // counting a run and keeping its address
+    pub(crate) fn add_run(&mut self, run: Run) {
+        self.add_count(run.kind, run.length);
+        self.runs.push(run);
+    }
+}
+
+// runs analysis of any ordered series, e.g. RR intervals, systolic pressures or step intervals;
+// values with a non-zero mask entry break the runs like annotated beats
+pub struct SeriesRuns<T: RunValue> {
+    rr_intervals: Vec<T>,
+    #[allow(dead_code)] // needed by the variance formula commented out in calculate_runs_variances
+    mean_rr: f64,
+    #[allow(dead_code)]
+    rr_length: usize,
+    annotations: Vec<u8>,
+    config: RunsConfig,
+    accumulator: RunsAccumulator,
+    censored: RunsAccumulator, // runs truncated by a boundary with the Censor policy
+    runs_variances: HashMap<RunType, Vec<f64>>,
+    analyzed: bool,
+    max_dec: usize,
+    max_acc: usize,
+    max_neu: usize,
+}
+
+// runs analysis of RR intervals in ms, with annotations as the mask
+pub type RRRuns = SeriesRuns<f64>;
+
+impl RRRuns {
+    // creating new instance of RRRuns - write_last_run decides whether the run cut by the end of the recording is counted
+    pub fn new(rr: Vec<f64>, annot: Vec<u8>, write_last_run: bool) -> Self {
+        let boundaries = BoundaryPolicy {
+            end: if write_last_run {
+                TruncatedRuns::Count
+            } else {
+                TruncatedRuns::Drop
+            },
+            ..BoundaryPolicy::default()
+        };
+        RRRuns::with_config(
+            rr,
+            annot,
+            RunsConfig {
+                boundaries,
+                ..RunsConfig::default()
+            },
+        )
+    }
+
+    // creating new instance of RRRuns with custom settings
+    pub fn with_config(rr: Vec<f64>, annot: Vec<u8>, config: RunsConfig) -> Self {
+        SeriesRuns::from_values(rr, Some(annot), config)
+    }
+
+    // returning the analysed RR intervals
+    pub fn rr_intervals(&self) -> &[f64] {
+        &self.rr_intervals
+    }
+}
+
+impl<T: RunValue> SeriesRuns<T> {
+    // creating a runs analysis of any series - without a mask all values are used
+    pub fn from_values(values: Vec<T>, mask: Option<Vec<u8>>, config: RunsConfig) -> Self {
+        let size = values.len();
+        let annotations = mask.unwrap_or_else(|| vec![0; size]);
+        let accumulator = RunsAccumulator::new();
+        let runs_variances: HashMap<RunType, Vec<f64>> = HashMap::new();
+        let mean_rr = total(&values) / size as f64;
+        SeriesRuns {
+            rr_intervals: values,
+            mean_rr,
+            rr_length: size,
+            annotations,
+            runs_variances,
+            config,
+            accumulator,
+            censored: RunsAccumulator::new(),
+            analyzed: false,
+            max_acc: 0,
+            max_dec: 0,
+            max_neu: 0,
+        }
+    }
+    pub fn get_nonzero_length(&self, map: &HashMap<usize, i32>) -> usize {
+        let mut max: &usize = &0;
+        for k in map.keys() {
+            if max < k {
+                max = k;
+            }
+        }
+        *max
+    }
+    // returning the analysed values
+    pub fn values(&self) -> &[T] {
+        &self.rr_intervals
+    }
+
+    // setting maximal runs lengths for future use
+
+    pub fn set_max(&mut self) {
+        self.max_dec = self.get_nonzero_length(&self.accumulator.dec);
+        self.max_acc = self.get_nonzero_length(&self.accumulator.acc);
+        self.max_neu = self.get_nonzero_length(&self.accumulator.neu);
+    }
+}
@@ -0,0 +1,42 @@
+use super::{RunValue, RunsAccumulator, RunsSummary, SeriesRuns};
+use crate::coarse_grain::{coarse_grain, coarse_grain_annotations};
+
+// runs analysis of the series coarse-grained at one scale
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleRuns {
+    pub scale: usize,
+    pub accumulator: RunsAccumulator, // run addresses are indices of the coarse-grained points
+    pub censored: RunsAccumulator,
+}
+
+impl ScaleRuns {
+    pub fn summary(&self) -> RunsSummary {
+        self.accumulator.summary()
+    }
+}
+
+impl<T: RunValue> SeriesRuns<T> {
+    // analyzing runs of the series coarse-grained at scales 1..=max_scale, with the settings of this instance;
+    // a coarse-grained point is annotated if any of its beats is
+    pub fn multiscale(&self, max_scale: usize) -> Vec<ScaleRuns> {
+        let size = self.rr_intervals.len().min(self.annotations.len());
+        let values: Vec<f64> = self.rr_intervals[..size]
+            .iter()
+            .map(|value| value.to_f64())
+            .collect();
+        (1..=max_scale)
+            .map(|scale| {
+                let mut runs = SeriesRuns::from_values(
+                    coarse_grain(&values, scale),
+                    Some(coarse_grain_annotations(&self.annotations[..size], scale)),
+                    self.config,
+                );
+                ScaleRuns {
+                    scale,
+                    accumulator: runs.get_full_runs().clone(),
+                    censored: runs.get_censored_runs().clone(),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,41 @@
+// optional printing layer on top of the structured reports
+use super::{RunType, RunValue, SeriesRuns};
+use std::fmt::Display;
+
+impl<T: RunValue + Display> SeriesRuns<T> {
+    // printing runs
+    pub fn print_runs(&mut self) {
+        print!("{}", self.summary());
+    }
+
+    // printing RR intervals of all runs of the given type and length, optionally with the reference beat
+    pub fn print_addresses(&mut self, run_type: RunType, run_length: usize, reference_beat: bool) {
+        println!("run type: {} run length: {}", run_type, run_length);
+        let reference_offset = if reference_beat { 0 } else { 1 };
+        for run in self
+            .runs()
+            .filter(|run| run.kind == run_type && run.length == run_length)
+        {
+            let start_idx = run.start + reference_offset;
+            println!("start_idx: {}, end_idx: {}", start_idx, run.end);
+            for value in &run.rr_values()[reference_offset..] {
+                print!("{} ", value);
+            }
+            println!();
+        }
+    }
+
+    pub fn print_runs_addresses(&mut self) {
+        print!("{}", self.runs_table());
+    }
+
+    pub fn print_runs_accumulator(&self) {
+        println!("dec: {:?}", self.accumulator.dec);
+        println!("acc: {:?}", self.accumulator.acc);
+        println!("neu: {:?}", self.accumulator.neu);
+    }
+
+    pub fn print_runs_variances(&self) {
+        println!("{:?}", self.runs_variances)
+    }
+}
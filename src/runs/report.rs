@@ -0,0 +1,185 @@
+use super::{Run, RunType, RunsAccumulator};
+use std::cmp;
+use std::fmt;
+use std::io;
+
+// one row of the runs summary - numbers of runs of a given length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunsSummaryRow {
+    pub length: usize,
+    pub acc: i32,
+    pub dec: i32,
+    pub neu: i32,
+}
+
+// numbers of acceleration, deceleration and neutral runs for lengths 1..=max
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunsSummary {
+    pub rows: Vec<RunsSummaryRow>,
+}
+
+impl RunsAccumulator {
+    // getting numbers of runs of each type for lengths 1..=max
+    pub fn summary(&self) -> RunsSummary {
+        // calculating max length to determine number of rows needed
+        let max_length = cmp::max(
+            cmp::max(self.max_length(RunType::Acc), self.max_length(RunType::Dec)),
+            self.max_length(RunType::Neu),
+        );
+        // building summary rows
+        let rows = (1..=max_length)
+            .map(|i| RunsSummaryRow {
+                length: i,
+                acc: self.count(RunType::Acc, i),
+                dec: self.count(RunType::Dec, i),
+                neu: self.count(RunType::Neu, i),
+            })
+            .collect();
+        RunsSummary { rows }
+    }
+}
+
+impl RunsSummary {
+    // writing the summary as CSV with a header row
+    pub fn write_csv<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["length", "ar", "dr", "n"])?;
+        for row in &self.rows {
+            writer.write_record([
+                row.length.to_string(),
+                row.acc.to_string(),
+                row.dec.to_string(),
+                row.neu.to_string(),
+            ])?;
+        }
+        writer.flush()
+    }
+
+    // serialising the summary as a JSON array of rows
+    pub fn to_json(&self) -> String {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{{\"length\":{},\"ar\":{},\"dr\":{},\"n\":{}}}",
+                    row.length, row.acc, row.dec, row.neu
+                )
+            })
+            .collect();
+        format!("[{}]", rows.join(","))
+    }
+}
+
+impl fmt::Display for RunsSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>6} {:>6} {:>6} {:>6}", "i", "AR", "DR", "N")?;
+        for row in &self.rows {
+            writeln!(
+                f,
+                "{:>6} {:>6} {:>6} {:>6}",
+                row.length, row.acc, row.dec, row.neu
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// all runs in the order they were found
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunsTable {
+    pub runs: Vec<Run>,
+}
+
+impl RunsTable {
+    // keeping only the runs of a given type and length
+    pub fn filter(&self, kind: RunType, length: usize) -> RunsTable {
+        RunsTable {
+            runs: self
+                .runs
+                .iter()
+                .filter(|run| run.kind == kind && run.length == length)
+                .copied()
+                .collect(),
+        }
+    }
+
+    // writing the runs as CSV with a header row
+    pub fn write_csv<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["type", "start", "end", "length"])?;
+        for run in &self.runs {
+            writer.write_record([
+                run.kind.to_string(),
+                run.start.to_string(),
+                run.end.to_string(),
+                run.length.to_string(),
+            ])?;
+        }
+        writer.flush()
+    }
+
+    // serialising the runs as a JSON array of objects
+    pub fn to_json(&self) -> String {
+        let runs: Vec<String> = self
+            .runs
+            .iter()
+            .map(|run| {
+                format!(
+                    "{{\"type\":\"{}\",\"start\":{},\"end\":{},\"length\":{}}}",
+                    run.kind, run.start, run.end, run.length
+                )
+            })
+            .collect();
+        format!("[{}]", runs.join(","))
+    }
+}
+
+impl fmt::Display for RunsTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4} {:>8} {:>8} {:>6}",
+            "type", "start", "end", "length"
+        )?;
+        for run in &self.runs {
+            writeln!(
+                f,
+                "{:>4} {:>8} {:>8} {:>6}",
+                run.kind, run.start, run.end, run.length
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// the complete result of a runs analysis
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunsReport {
+    pub summary: RunsSummary,
+    pub runs: RunsTable,
+    pub censored: RunsSummary, // runs truncated by a boundary with the Censor policy
+}
+
+impl RunsReport {
+    // serialising the report as a JSON object with the summary and the runs
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"summary\":{},\"runs\":{},\"censored\":{}}}",
+            self.summary.to_json(),
+            self.runs.to_json(),
+            self.censored.to_json()
+        )
+    }
+}
+
+impl fmt::Display for RunsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.summary)?;
+        write!(f, "{}", self.runs)?;
+        if !self.censored.rows.is_empty() {
+            write!(f, "\ncensored\n{}", self.censored)?;
+        }
+        Ok(())
+    }
+}
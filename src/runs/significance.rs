@@ -0,0 +1,333 @@
+use super::{RunType, RunValue, SeriesRuns, TruncatedRuns};
+use crate::rng::Rng;
+use crate::stats::chi_square_sf;
+use std::collections::HashMap;
+
+// source of the expected numbers of runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunsExpectation {
+    // i.i.d. continuous data (Levene and Wolfowitz) - acceleration and deceleration runs only,
+    // valid for the default exact neutral tolerance
+    Iid,
+    // the mean over surrogates in which the normal beats are shuffled among themselves
+    Shuffled { surrogates: usize, seed: u64 },
+}
+
+// direction of a significant departure from the expected number of runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Representation {
+    Over,
+    Under,
+    AsExpected,
+}
+
+// comparison of the observed and expected number of runs of one type and length
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunLengthTest {
+    pub kind: RunType,
+    pub length: usize,
+    pub observed: i32,
+    pub expected: f64,
+    pub residual: f64, // (observed - expected) / sqrt(expected)
+    pub p_value: f64,
+    pub representation: Representation,
+}
+
+// comparison of the whole runs distribution with its expectation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunsDistributionTest {
+    pub expectation: RunsExpectation,
+    pub lengths: Vec<RunLengthTest>,
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).map(|i| i as f64).product()
+}
+
+// expected number of deceleration (and, by symmetry, acceleration) runs of length k,
+// i.e. k consecutive increases, in n i.i.d. continuous values (Levene and Wolfowitz 1944)
+pub fn expected_iid_runs(n: usize, k: usize) -> f64 {
+    if k == 0 || n < 2 || k > n - 1 {
+        return 0.0;
+    }
+    if k == n - 1 {
+        return 1.0 / factorial(n);
+    }
+    let (n_f, k_f) = (n as f64, k as f64);
+    ((k_f * k_f + 3.0 * k_f + 1.0) * n_f - (k_f.powi(3) + 3.0 * k_f * k_f - k_f - 4.0))
+        / factorial(k + 3)
+}
+
+// probability that the final (or, by symmetry, the first) run of n i.i.d. continuous values
+// is a deceleration run of length k
+fn boundary_iid_run_probability(n: usize, k: usize) -> f64 {
+    if k == 0 || n < 2 || k > n - 1 {
+        0.0
+    } else if k == n - 1 {
+        1.0 / factorial(n)
+    } else {
+        1.0 / factorial(k + 1) - 1.0 / factorial(k + 2)
+    }
+}
+
+// Pearson statistic with tail cells pooled until each expected count reaches 5
+fn pooled_chi_square(observed: &[f64], expected: &[f64]) -> (f64, usize) {
+    let mut cells: Vec<(f64, f64)> = Vec::new();
+    let mut pending = (0.0, 0.0);
+    for (o, e) in observed.iter().zip(expected).rev() {
+        pending = (pending.0 + o, pending.1 + e);
+        if pending.1 >= 5.0 {
+            cells.push(pending);
+            pending = (0.0, 0.0);
+        }
+    }
+    if pending.0 > 0.0 || pending.1 > 0.0 {
+        match cells.last_mut() {
+            Some(last) => *last = (last.0 + pending.0, last.1 + pending.1),
+            None => cells.push(pending),
+        }
+    }
+    let statistic = cells
+        .iter()
+        .filter(|(_, e)| *e > 0.0)
+        .map(|(o, e)| (o - e).powi(2) / e)
+        .sum();
+    (statistic, cells.len())
+}
+
+impl<T: RunValue> SeriesRuns<T> {
+    fn counts(&self, kind: RunType) -> &HashMap<usize, i32> {
+        self.accumulator.counts(kind)
+    }
+
+    // expected numbers of deceleration runs per length (index 0 - length 1) under the i.i.d. hypothesis,
+    // summed over the stretches of consecutive normal beats, without the truncated runs that are not counted
+    fn expected_iid_counts(&self, max_length: usize) -> Vec<f64> {
+        let size = self.rr_intervals.len().min(self.annotations.len());
+        let boundaries = self.config.boundaries;
+        let mut expected = vec![0.0; max_length];
+        let mut segment_start = 0;
+        for i in 0..=size {
+            if i < size && self.annotations[i] == 0 {
+                continue;
+            }
+            let segment_length = i - segment_start;
+            let first_policy = if segment_start == 0 {
+                boundaries.start
+            } else {
+                boundaries.artifact
+            };
+            let last_policy = if i == size {
+                boundaries.end
+            } else {
+                boundaries.artifact
+            };
+            let first_removed = first_policy != TruncatedRuns::Count;
+            let last_removed = last_policy != TruncatedRuns::Count;
+            for (k, e) in expected.iter_mut().enumerate() {
+                let boundary = boundary_iid_run_probability(segment_length, k + 1);
+                *e += expected_iid_runs(segment_length, k + 1);
+                if first_removed {
+                    *e -= boundary;
+                }
+                if last_removed {
+                    *e -= boundary;
+                }
+                // a run spanning the whole segment is both the first and the last one
+                if first_removed && last_removed && k + 2 == segment_length {
+                    *e += boundary;
+                }
+            }
+            segment_start = i + 1;
+        }
+        expected
+    }
+
+    // runs counts of a surrogate with the normal beats shuffled
+    fn shuffled_counts(&self, rng: &mut Rng) -> [HashMap<usize, i32>; 3] {
+        let normal: Vec<usize> = (0..self.rr_intervals.len().min(self.annotations.len()))
+            .filter(|&i| self.annotations[i] == 0)
+            .collect();
+        let mut values: Vec<T> = normal.iter().map(|&i| self.rr_intervals[i]).collect();
+        rng.shuffle(&mut values);
+        let mut rr = self.rr_intervals.clone();
+        for (&i, value) in normal.iter().zip(values) {
+            rr[i] = value;
+        }
+        let mut surrogate =
+            SeriesRuns::from_values(rr, Some(self.annotations.clone()), self.config);
+        surrogate.analyze_runs();
+        [
+            surrogate.accumulator.dec,
+            surrogate.accumulator.acc,
+            surrogate.accumulator.neu,
+        ]
+    }
+
+    // testing the runs distribution against i.i.d. theory or shuffled surrogates;
+    // run lengths with p_value < alpha are marked as over- or under-represented
+    pub fn test_runs_distribution(
+        &mut self,
+        expectation: RunsExpectation,
+        alpha: f64,
+    ) -> RunsDistributionTest {
+        if !self.analyzed {
+            self.analyze_runs();
+        }
+        let kinds = [RunType::Dec, RunType::Acc, RunType::Neu];
+        let observed_max =
+            |runs: &SeriesRuns<T>, kind| runs.counts(kind).keys().max().copied().unwrap_or(0);
+
+        // expected counts and per length p-values, indexed like `kinds`
+        let (expected, p_values, surrogate_statistics): (Vec<Vec<f64>>, Vec<Vec<f64>>, Vec<f64>) =
+            match expectation {
+                RunsExpectation::Iid => {
+                    // lengths up to the longest observed run or the last one expected at least once in two recordings
+                    let mut dec = self.expected_iid_counts(self.rr_intervals.len());
+                    let observed =
+                        observed_max(self, RunType::Dec).max(observed_max(self, RunType::Acc));
+                    let max_length = dec
+                        .iter()
+                        .rposition(|&e| e >= 0.5)
+                        .map_or(0, |k| k + 1)
+                        .max(observed);
+                    dec.truncate(max_length);
+                    let expected = vec![dec.clone(), dec, vec![0.0; max_length]];
+                    let p_values = kinds
+                        .iter()
+                        .zip(&expected)
+                        .map(|(&kind, e)| {
+                            e.iter()
+                                .enumerate()
+                                .map(|(k, &e)| {
+                                    let o = *self.counts(kind).get(&(k + 1)).unwrap_or(&0) as f64;
+                                    chi_square_sf((o - e).powi(2) / e, 1.0)
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    (expected, p_values, Vec::new())
+                }
+                RunsExpectation::Shuffled { surrogates, seed } => {
+                    let mut rng = Rng::new(seed);
+                    let samples: Vec<[HashMap<usize, i32>; 3]> = (0..surrogates)
+                        .map(|_| self.shuffled_counts(&mut rng))
+                        .collect();
+                    let max_length = (0..3)
+                        .map(|t| {
+                            samples
+                                .iter()
+                                .filter_map(|s| s[t].keys().max().copied())
+                                .max()
+                                .unwrap_or(0)
+                                .max(observed_max(self, kinds[t]))
+                        })
+                        .max()
+                        .unwrap_or(0);
+                    let count =
+                        |map: &HashMap<usize, i32>, k: usize| *map.get(&k).unwrap_or(&0) as f64;
+                    let expected: Vec<Vec<f64>> = (0..3)
+                        .map(|t| {
+                            (1..=max_length)
+                                .map(|k| {
+                                    samples.iter().map(|s| count(&s[t], k)).sum::<f64>()
+                                        / surrogates as f64
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    // two-sided permutation p-values per length
+                    let p_values = (0..3)
+                        .map(|t| {
+                            (1..=max_length)
+                                .map(|k| {
+                                    let e = expected[t][k - 1];
+                                    let departure = (count(self.counts(kinds[t]), k) - e).abs();
+                                    let extreme = samples
+                                        .iter()
+                                        .filter(|s| (count(&s[t], k) - e).abs() >= departure)
+                                        .count();
+                                    (1 + extreme) as f64 / (surrogates + 1) as f64
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    // chi-square statistics of the surrogates themselves, for the rank p-value
+                    let statistics = samples
+                        .iter()
+                        .map(|s| {
+                            (0..3)
+                                .map(|t| {
+                                    let o: Vec<f64> =
+                                        (1..=max_length).map(|k| count(&s[t], k)).collect();
+                                    pooled_chi_square(&o, &expected[t]).0
+                                })
+                                .sum()
+                        })
+                        .collect();
+                    (expected, p_values, statistics)
+                }
+            };
+
+        let mut lengths = Vec::new();
+        let mut chi_square = 0.0;
+        let mut cells = 0;
+        for (t, &kind) in kinds.iter().enumerate() {
+            if kind == RunType::Neu && expectation == RunsExpectation::Iid {
+                continue;
+            }
+            let observed: Vec<f64> = (1..=expected[t].len())
+                .map(|k| *self.counts(kind).get(&k).unwrap_or(&0) as f64)
+                .collect();
+            if observed.iter().all(|&o| o == 0.0) && expected[t].iter().all(|&e| e == 0.0) {
+                continue;
+            }
+            let (statistic, n_cells) = pooled_chi_square(&observed, &expected[t]);
+            chi_square += statistic;
+            cells += n_cells;
+            for (k, (&o, &e)) in observed.iter().zip(&expected[t]).enumerate() {
+                if o == 0.0 && e == 0.0 {
+                    continue;
+                }
+                let p_value = p_values[t][k];
+                let representation = if p_value >= alpha {
+                    Representation::AsExpected
+                } else if o > e {
+                    Representation::Over
+                } else {
+                    Representation::Under
+                };
+                lengths.push(RunLengthTest {
+                    kind,
+                    length: k + 1,
+                    observed: o as i32,
+                    expected: e,
+                    residual: (o - e) / e.sqrt(),
+                    p_value,
+                    representation,
+                });
+            }
+        }
+        let degrees_of_freedom = cells.saturating_sub(1).max(1);
+        let p_value = match expectation {
+            RunsExpectation::Iid => chi_square_sf(chi_square, degrees_of_freedom as f64),
+            RunsExpectation::Shuffled { surrogates, .. } => {
+                let extreme = surrogate_statistics
+                    .iter()
+                    .filter(|&&s| s >= chi_square)
+                    .count();
+                (1 + extreme) as f64 / (surrogates + 1) as f64
+            }
+        };
+        RunsDistributionTest {
+            expectation,
+            lengths,
+            chi_square,
+            degrees_of_freedom,
+            p_value,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Run, RunType, RunValue, RunsAccumulator, RunsConfig, RunsSummary, TruncatedRuns};
+
+// a completed run together with what the boundary policies decided about it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RunEvent {
+    pub run: Run,
+    pub status: TruncatedRuns, // Count for runs that are not truncated
+}
+
+// the runs state machine shared by the batch and the streaming analyzers;
+// beats are fed one at a time and every completed run is returned once
+#[derive(Debug, Clone)]
+pub(crate) struct RunsTracker<T> {
+    config: RunsConfig,
+    previous: Option<(T, u8)>, // value and annotation of the last beat
+    beats: usize,              // number of beats seen so far
+    // type, length and start truncation of the run in progress
+    current: Option<(RunType, usize, Option<TruncatedRuns>)>,
+}
+
+impl<T: RunValue> RunsTracker<T> {
+    pub(crate) fn new(config: RunsConfig) -> Self {
+        RunsTracker {
+            config,
+            previous: None,
+            beats: 0,
+            current: None,
+        }
+    }
+
+    // closing the run in progress, which ended with the beat `end`
+    fn close(&mut self, end: usize, truncated_end: Option<TruncatedRuns>) -> Option<RunEvent> {
+        let (kind, length, truncated_start) = self.current.take()?;
+        Some(RunEvent {
+            run: Run {
+                start: end - length,
+                end,
+                length,
+                kind,
+            },
+            status: truncated_start
+                .max(truncated_end)
+                .unwrap_or(TruncatedRuns::Count),
+        })
+    }
+
+    // taking the next beat - step (beats - 1, beats) is only used if both beats are normal
+    pub(crate) fn push(&mut self, rr: T, annotation: u8) -> Option<RunEvent> {
+        let index = self.beats;
+        self.beats += 1;
+        let (previous_rr, previous_annotation) = self.previous.replace((rr, annotation))?;
+        let boundaries = self.config.boundaries;
+        let step = match self.config.classify(previous_rr, rr) {
+            Some(step) if previous_annotation == 0 && annotation == 0 => step,
+            // an annotated beat (or a NaN) breaks the run, which ended at the previous beat
+            _ => return self.close(index - 1, Some(boundaries.artifact)),
+        };
+        match self.current {
+            Some((kind, length, truncated))
+                if kind == step || (step == RunType::Neu && self.config.neutral_continues_run) =>
+            {
+                self.current = Some((kind, length + 1, truncated));
+                None
+            }
+            Some(_) => {
+                let event = self.close(index - 1, None);
+                self.current = Some((step, 1, None));
+                event
+            }
+            None => {
+                // the run begins with the recording or right after an annotated beat
+                let truncated = if index == 1 {
+                    boundaries.start
+                } else {
+                    boundaries.artifact
+                };
+                self.current = Some((step, 1, Some(truncated)));
+                None
+            }
+        }
+    }
+
+    // closing the run cut by the end of the recording; the next beat has no step to the last one
+    pub(crate) fn finish(&mut self) -> Option<RunEvent> {
+        self.previous = None;
+        let end = self.beats.checked_sub(1)?;
+        self.close(end, Some(self.config.boundaries.end))
+    }
+
+    pub(crate) fn beats(&self) -> usize {
+        self.beats
+    }
+
+    pub(crate) fn current(&self) -> Option<(RunType, usize)> {
+        self.current.map(|(kind, length, _)| (kind, length))
+    }
+}
+
+// incremental runs analysis for RR intervals arriving one at a time, e.g. from a chest strap;
+// only the numbers of runs are kept, so memory does not grow with the length of the feed
+#[derive(Debug, Clone)]
+pub struct RunsStream<T = f64> {
+    tracker: RunsTracker<T>,
+    accumulator: RunsAccumulator,
+    censored: RunsAccumulator,
+}
+
+impl<T: RunValue> RunsStream<T> {
+    pub fn new(config: RunsConfig) -> Self {
+        RunsStream {
+            tracker: RunsTracker::new(config),
+            accumulator: RunsAccumulator::new(),
+            censored: RunsAccumulator::new(),
+        }
+    }
+
+    fn record(&mut self, event: Option<RunEvent>) -> Option<RunEvent> {
+        if let Some(RunEvent { run, status }) = event {
+            match status {
+                TruncatedRuns::Count => self.accumulator.add_count(run.kind, run.length),
+                TruncatedRuns::Censor => self.censored.add_count(run.kind, run.length),
+                TruncatedRuns::Drop => {}
+            }
+        }
+        event
+    }
+
+    // taking the next RR interval and its annotation, returning the run completed by this beat, if any
+    pub fn push(&mut self, rr: T, annotation: u8) -> Option<RunEvent> {
+        let event = self.tracker.push(rr, annotation);
+        self.record(event)
+    }
+
+    // closing the run in progress according to the end policy, e.g. when the feed stops;
+    // further beats start a new recording-internal run as if after an annotated beat
+    pub fn finish(&mut self) -> Option<RunEvent> {
+        let event = self.tracker.finish();
+        self.record(event)
+    }
+
+    // getting the numbers of completed runs
+    pub fn summary(&self) -> RunsSummary {
+        self.accumulator.summary()
+    }
+
+    pub fn accumulator(&self) -> &RunsAccumulator {
+        &self.accumulator
+    }
+
+    pub fn censored(&self) -> &RunsAccumulator {
+        &self.censored
+    }
+
+    // getting the type and length of the run in progress
+    pub fn current_run(&self) -> Option<(RunType, usize)> {
+        self.tracker.current()
+    }
+
+    pub fn beats(&self) -> usize {
+        self.tracker.beats()
+    }
+}
@@ -0,0 +1,79 @@
+use super::{RunValue, RunView, RunsAccumulator, RunsReport, RunsSummary, RunsTable, SeriesRuns};
+
+impl<T: RunValue> SeriesRuns<T> {
+    pub fn get_runs_summary(&mut self) -> Vec<Vec<i32>> {
+        let mut summary: Vec<Vec<i32>> = self
+            .summary()
+            .rows
+            .iter()
+            .map(|row| vec![row.acc, row.dec, row.neu])
+            .collect();
+
+        // if summary is empty (no runs found), return a single row of zeros
+        if summary.is_empty() {
+            summary.push(vec![0, 0, 0]);
+        }
+
+        summary
+    }
+
+    // getting numbers of runs of each type for lengths 1..=max
+    pub fn summary(&mut self) -> RunsSummary {
+        if !self.analyzed {
+            self.analyze_runs();
+        }
+        self.accumulator.summary()
+    }
+
+    // getting numbers of censored runs, i.e. runs truncated by a boundary with the Censor policy
+    pub fn censored_summary(&mut self) -> RunsSummary {
+        self.get_censored_runs().summary()
+    }
+
+    // getting all runs in the order they were found
+    pub fn runs_table(&mut self) -> RunsTable {
+        if !self.analyzed {
+            self.analyze_runs();
+        }
+        RunsTable {
+            runs: self.accumulator.runs.clone(),
+        }
+    }
+
+    // getting the summary and the runs in one report
+    pub fn report(&mut self) -> RunsReport {
+        RunsReport {
+            summary: self.summary(),
+            runs: self.runs_table(),
+            censored: self.censored_summary(),
+        }
+    }
+
+    // iterating over the runs in the order they were found
+    pub fn runs(&mut self) -> impl Iterator<Item = RunView<'_, T>> {
+        if !self.analyzed {
+            self.analyze_runs();
+        }
+        let rr = &self.rr_intervals;
+        self.accumulator
+            .runs
+            .iter()
+            .map(move |run| RunView { run, rr })
+    }
+
+    // getting full runs
+    pub fn get_full_runs(&mut self) -> &RunsAccumulator {
+        if !self.analyzed {
+            self.analyze_runs();
+        }
+        &self.accumulator
+    }
+
+    // getting censored runs
+    pub fn get_censored_runs(&mut self) -> &RunsAccumulator {
+        if !self.analyzed {
+            self.analyze_runs();
+        }
+        &self.censored
+    }
+}
@@ -0,0 +1,34 @@
+use super::{RunType, RunValue, SeriesRuns};
+use std::collections::HashMap;
+
+impl<T: RunValue> SeriesRuns<T> {
+    pub fn calculate_runs_variances(&mut self) -> &HashMap<RunType, Vec<f64>> {
+        if !self.analyzed {
+            self.analyze_runs();
+        }
+        for run in &self.accumulator.runs {
+            let max_len = match run.kind {
+                RunType::Dec => self.max_dec,
+                RunType::Acc => self.max_acc,
+                RunType::Neu => self.max_neu,
+            };
+            // this either accesses an existing vector containing variances of runs of specific lengths, or creates if, if it does,
+            // i.e. it may return a reference to the vector of decelerations runs variances vector, each of the entries contains the variance of
+            // a specific length and direction: index 0 - cumulative variance of all deceleration runs of length 1,
+            // index 1: - cumulative variance of all deceleration runs of length 2 etc.
+            let run_var = self
+                .runs_variances
+                .entry(run.kind)
+                .or_insert_with(|| vec![0.0; max_len]);
+            let mut local_run_variance = 0.0; // initial variance - it is 0, of course - it will be cumulatively calculated in the loop below
+            for i in run.start..run.end {
+                //local_run_variance += (&self.rr_intervals[i as usize] - self.mean_rr).powi(2)
+                //    / (2.0 * (self.rr_length as f64).powi(2));
+                // the expression below is for testing, i.e. do we get the correct locations of runs, for example, 0, 1, 1, 0, 0 should have 2 at position 2
+                local_run_variance += self.rr_intervals[i + 1].to_f64()
+            }
+            run_var[run.length - 1] += local_run_variance;
+        }
+        &self.runs_variances
+    }
+}
@@ -0,0 +1,86 @@
+use super::{RunValue, RunsSummary, SeriesRuns};
+
+// window length or step, in beats or in time (in the units of the RR intervals, e.g. 300000.0 for 5 minutes in ms)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowSize {
+    Beats(usize),
+    Time(f64),
+}
+
+// runs analysis of a single window
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowRuns {
+    pub start: usize, // index of the first beat in the window
+    pub end: usize,   // index one past the last beat in the window
+    pub onset: f64,   // time of the first beat, on the same axis as Run::onset
+    pub offset: f64,  // time of the last beat
+    pub longest_dec: usize,
+    pub longest_acc: usize,
+    pub longest_neu: usize,
+    pub mean_run_length: f64, // mean length of all runs in the window, NaN if there are none
+    pub summary: RunsSummary,
+}
+
+impl<T: RunValue> SeriesRuns<T> {
+    // analyzing runs separately in sliding windows; only windows lying entirely within the recording are returned,
+    // and each window is analyzed with the settings of this instance, so its first and last runs are truncated by the window
+    pub fn windowed(&self, window: WindowSize, step: WindowSize) -> Vec<WindowRuns> {
+        let size = self.rr_intervals.len().min(self.annotations.len());
+        // beat times - time of beat i is the sum of RR intervals up to and including i
+        let times: Vec<f64> = self.rr_intervals[..size]
+            .iter()
+            .scan(0.0, |time, rr| {
+                *time += rr.to_f64();
+                Some(*time)
+            })
+            .collect();
+        // index one past the last beat occurring before `time`
+        let beats_before = |time: f64| times.partition_point(|&t| t < time);
+
+        let mut windows = Vec::new();
+        let mut start = 0;
+        while start < size {
+            let end = match window {
+                WindowSize::Beats(beats) => start + beats,
+                WindowSize::Time(duration) => {
+                    if times[size - 1] < times[start] + duration {
+                        break;
+                    }
+                    beats_before(times[start] + duration)
+                }
+            };
+            if end > size || end <= start {
+                break;
+            }
+            windows.push(self.analyze_window(start, end, &times));
+            start = match step {
+                WindowSize::Beats(beats) => start + beats.max(1),
+                WindowSize::Time(duration) => beats_before(times[start] + duration).max(start + 1),
+            };
+        }
+        windows
+    }
+
+    fn analyze_window(&self, start: usize, end: usize, times: &[f64]) -> WindowRuns {
+        let mut runs = SeriesRuns::from_values(
+            self.rr_intervals[start..end].to_vec(),
+            Some(self.annotations[start..end].to_vec()),
+            self.config,
+        );
+        let summary = runs.summary();
+        let (total_runs, total_length) = runs.runs().fold((0, 0), |(count, length), run| {
+            (count + 1, length + run.length)
+        });
+        WindowRuns {
+            start,
+            end,
+            onset: times[start],
+            offset: times[end - 1],
+            longest_dec: runs.max_dec,
+            longest_acc: runs.max_acc,
+            longest_neu: runs.max_neu,
+            mean_run_length: total_length as f64 / total_runs as f64,
+            summary,
+        }
+    }
+}
@@ -63,6 +63,335 @@ fn ncm_correlation_sums(signal: &[f64], m: usize, r: f64) -> Vec<f64> {
 /// * f64 - the calculated sample entropy
 pub fn calc_samp_en(signal: &[f64], m: usize, r: f64) -> f64 {
     let cm = ncm_correlation_sums(signal, m, r);
-    let sampen = cm[0].ln() - cm[1].ln();
-    sampen
+    cm[0].ln() - cm[1].ln()
+}
+
+// flags of a template match
+const MATCH_B: u8 = 1; // templates of length m match
+const MATCH_A: u8 = 2; // templates of length m + 1 match
+
+/// Sample Entropy Estimate With Its Standard Error
+///
+/// Holds the raw match counts A and B of Richman and Moorman together
+/// with the variance estimate of Lake et al. (2002), which accounts for
+/// the dependence between matches of overlapping templates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampEnEstimate {
+    /// the template length (embedding dimension)
+    pub m: usize,
+    /// the radius of comparison
+    pub r: f64,
+    /// the strategy that produced `r`
+    pub tolerance: Tolerance,
+    /// number of matching template pairs of length m + 1
+    pub a: u64,
+    /// number of matching template pairs of length m
+    pub b: u64,
+    /// the conditional probability A/B
+    pub cp: f64,
+    /// standard error of the conditional probability
+    pub cp_se: f64,
+    /// the sample entropy, -ln(A/B)
+    pub sampen: f64,
+    /// standard error of the sample entropy
+    pub se: f64,
+}
+
+impl SampEnEstimate {
+    /// Confidence Interval for Sample Entropy
+    ///
+    /// # Parameters
+    /// * `level`: f64 - the confidence level, e.g. 0.95
+    /// # Returns
+    /// * (f64, f64) - lower and upper bounds of the normal-approximation interval
+    pub fn confidence_interval(&self, level: f64) -> (f64, f64) {
+        let z = crate::stats::normal_quantile(0.5 + level / 2.0);
+        (self.sampen - z * self.se, self.sampen + z * self.se)
+    }
+}
+
+// listing the matches of every template with the later templates as (template, flags),
+// sorted by template, so that memory grows with the number of matches rather than with N²;
+// both lengths m and m + 1 use the same N - m templates, so that A <= B always holds
+fn template_matches(signal: &[f64], m: usize, r: f64) -> Vec<Vec<(usize, u8)>> {
+    let n_templates = signal.len().saturating_sub(m);
+    (0..n_templates)
+        .map(|i| {
+            ((i + 1)..n_templates)
+                .filter_map(|j| {
+                    let mut max_diff = 0.0;
+                    for k in 0..m {
+                        let diff = (signal[i + k] - signal[j + k]).abs();
+                        if diff > max_diff {
+                            max_diff = diff;
+                        }
+                    }
+                    if max_diff > r {
+                        return None;
+                    }
+                    let mut flag = MATCH_B;
+                    if (signal[i + m] - signal[j + m]).abs() <= r {
+                        flag |= MATCH_A;
+                    }
+                    Some((j, flag))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// the flags of the match between templates x < y, 0 if they do not match
+fn match_flag(matches: &[Vec<(usize, u8)>], x: usize, y: usize) -> u8 {
+    matches[x]
+        .binary_search_by_key(&y, |&(j, _)| j)
+        .map_or(0, |k| matches[x][k].1)
+}
+
+// counting ordered pairs of distinct matches whose templates overlap (K_A or K_B in Lake et al.);
+// templates overlap if they start at most `overlap` points apart, i.e. length - 1
+fn overlapping_match_pairs(matches: &[Vec<(usize, u8)>], overlap: usize, flag: u8) -> f64 {
+    let n = matches.len();
+    let mut degree = vec![0u64; n];
+    for (i, row) in matches.iter().enumerate() {
+        for &(j, _) in row.iter().filter(|&&(_, f)| f & flag != 0) {
+            degree[i] += 1;
+            degree[j] += 1;
+        }
+    }
+    let mut pairs = 0u64;
+    let mut neighbourhood: Vec<usize> = Vec::with_capacity(4 * overlap + 2);
+    for (i, row) in matches.iter().enumerate() {
+        for &(j, _) in row.iter().filter(|&&(_, f)| f & flag != 0) {
+            // templates overlapping template i or template j, in increasing order
+            neighbourhood.clear();
+            let i_end = (i + overlap).min(n - 1);
+            neighbourhood.extend(i.saturating_sub(overlap)..=i_end);
+            neighbourhood
+                .extend(j.saturating_sub(overlap).max(i_end + 1)..=(j + overlap).min(n - 1));
+            // matches with at least one template in the neighbourhood
+            let mut touching: u64 = neighbourhood.iter().map(|&x| degree[x]).sum();
+            for (idx, &x) in neighbourhood.iter().enumerate() {
+                for &y in &neighbourhood[(idx + 1)..] {
+                    if match_flag(matches, x, y) & flag != 0 {
+                        touching -= 1;
+                    }
+                }
+            }
+            // the match (i, j) itself is not a pair
+            pairs += touching - 1;
+        }
+    }
+    pairs as f64
+}
+
+/// Calculate Sample Entropy With Match Counts and Standard Error
+///
+/// This function counts the matching template pairs of length m (B) and
+/// m + 1 (A) among the first N - m templates (Richman and Moorman), and
+/// estimates the variance of the conditional probability A/B following
+/// Lake et al. (2002):
+/// var(CP) = CP(1 - CP)/B + (K_A - CP^2 K_B)/B^2,
+/// where K_A and K_B count pairs of matches sharing overlapping templates.
+/// The standard error of SampEn is then se(CP)/CP.
+///
+/// Note that here `m` is the template length, so `m = 2` compares
+/// templates of length 2 and 3. `calc_samp_en` always compares templates
+/// of length 1 and 2 (its `m` is the number of correlation sums), so it
+/// corresponds to this estimate with `m = 1`; the two differ slightly, as
+/// `calc_samp_en` normalises each correlation sum over all templates of its
+/// length, while A and B here are counted among the same N - m templates.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data for which the Sample Entropy is to be calculated
+/// * `m`: usize - the template length (embedding dimension)
+/// * `r`: f64 - the radius of comparison
+/// # Returns
+/// * SampEnEstimate - the counts, the sample entropy and its standard error
+pub fn calc_samp_en_estimate(signal: &[f64], m: usize, r: f64) -> SampEnEstimate {
+    let matches = template_matches(signal, m, r);
+    let mut a = 0u64;
+    let mut b = 0u64;
+    for &(_, flag) in matches.iter().flatten() {
+        if flag & MATCH_B != 0 {
+            b += 1;
+        }
+        if flag & MATCH_A != 0 {
+            a += 1;
+        }
+    }
+    let cp = a as f64 / b as f64;
+    // A counts templates of length m + 1, B templates of length m
+    let k_a = overlapping_match_pairs(&matches, m, MATCH_A);
+    let k_b = overlapping_match_pairs(&matches, m.saturating_sub(1), MATCH_B);
+    let b_f = b as f64;
+    let cp_variance = cp * (1.0 - cp) / b_f + (k_a - cp * cp * k_b) / (b_f * b_f);
+    let cp_se = cp_variance.max(0.0).sqrt();
+    SampEnEstimate {
+        m,
+        r,
+        tolerance: Tolerance::Absolute(r),
+        a,
+        b,
+        cp,
+        cp_se,
+        sampen: -cp.ln(),
+        se: cp_se / cp,
+    }
+}
+
+/// Calculate Approximate Entropy
+///
+/// Pincus' ApEn, Phi_m(r) - Phi_{m+1}(r), where Phi_m is the mean
+/// logarithm of the fraction of templates of length m lying within r
+/// of each template (self-matches included).
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data for which the Approximate Entropy is to be calculated
+/// * `m`: usize - the template length (embedding dimension)
+/// * `r`: f64 - the radius of comparison
+/// # Returns
+/// * f64 - the calculated approximate entropy
+pub fn calc_ap_en(signal: &[f64], m: usize, r: f64) -> f64 {
+    let phi = |length: usize| -> f64 {
+        let n_templates = signal.len() + 1 - length;
+        let mut log_sum = 0.0;
+        for i in 0..n_templates {
+            let mut count = 0;
+            for j in 0..n_templates {
+                if (0..length).all(|k| (signal[i + k] - signal[j + k]).abs() <= r) {
+                    count += 1;
+                }
+            }
+            log_sum += (count as f64 / n_templates as f64).ln();
+        }
+        log_sum / n_templates as f64
+    };
+    if signal.len() < m + 2 {
+        return f64::NAN;
+    }
+    phi(m) - phi(m + 1)
+}
+
+/// Strategies for Choosing the Radius of Comparison r
+///
+/// The data-driven strategies search r over fractions of the signal SD
+/// from 0.02 to 1.0 in steps of 0.02.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    /// r given directly, in the units of the signal
+    Absolute(f64),
+    /// r as a fraction of the signal SD, e.g. 0.2
+    SdFraction(f64),
+    /// r minimising max(se(CP)/CP, se(SampEn)/SampEn) (Lake et al. 2002)
+    MinRelativeError,
+    /// r maximising approximate entropy, r_max (Chon et al. 2009)
+    MaxApEn,
+    /// Chon's empirical approximation of r_max, available for m = 2 and m = 3
+    Chon,
+}
+
+// the grid of SD fractions searched by the data-driven strategies
+fn sd_fraction_grid() -> impl Iterator<Item = f64> {
+    (1..=50).map(|i| i as f64 * 0.02)
+}
+
+/// Select the Radius of Comparison
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data for which the entropy will be calculated
+/// * `m`: usize - the template length (embedding dimension)
+/// * `tolerance`: Tolerance - the strategy for choosing r
+/// # Returns
+/// * f64 - the radius of comparison r, in the units of the signal
+pub fn select_tolerance(signal: &[f64], m: usize, tolerance: Tolerance) -> f64 {
+    let sd = crate::stats::std_dev(signal);
+    match tolerance {
+        Tolerance::Absolute(r) => r,
+        Tolerance::SdFraction(fraction) => fraction * sd,
+        Tolerance::MinRelativeError => {
+            let mut best = (f64::INFINITY, f64::NAN);
+            for r in sd_fraction_grid().map(|fraction| fraction * sd) {
+                let estimate = calc_samp_en_estimate(signal, m, r);
+                let relative_error =
+                    (estimate.cp_se / estimate.cp).max(estimate.se / estimate.sampen);
+                if relative_error.is_finite() && relative_error < best.0 {
+                    best = (relative_error, r);
+                }
+            }
+            best.1
+        }
+        Tolerance::MaxApEn => {
+            let mut best = (f64::NEG_INFINITY, f64::NAN);
+            for r in sd_fraction_grid().map(|fraction| fraction * sd) {
+                let ap_en = calc_ap_en(signal, m, r);
+                if ap_en > best.0 {
+                    best = (ap_en, r);
+                }
+            }
+            best.1
+        }
+        Tolerance::Chon => {
+            // the ratio of short-term (first differences) to long-term variability
+            let differences: Vec<f64> = signal.windows(2).map(|w| w[1] - w[0]).collect();
+            let ratio = (crate::stats::std_dev(&differences) / sd).sqrt();
+            let length_factor = (signal.len() as f64 / 1000.0).powf(0.25);
+            let fraction = match m {
+                2 => (-0.036 + 0.26 * ratio) / length_factor,
+                3 => (-0.08 + 0.46 * ratio) / length_factor,
+                _ => f64::NAN,
+            };
+            fraction * sd
+        }
+    }
+}
+
+/// Calculate Sample Entropy With an Automatically Selected Tolerance
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data for which the Sample Entropy is to be calculated
+/// * `m`: usize - the template length (embedding dimension)
+/// * `tolerance`: Tolerance - the strategy for choosing r
+/// # Returns
+/// * SampEnEstimate - the estimate, recording the strategy and the selected r
+pub fn calc_samp_en_with_tolerance(
+    signal: &[f64],
+    m: usize,
+    tolerance: Tolerance,
+) -> SampEnEstimate {
+    let r = select_tolerance(signal, m, tolerance);
+    SampEnEstimate {
+        tolerance,
+        ..calc_samp_en_estimate(signal, m, r)
+    }
+}
+
+/// Calculate Quadratic Sample Entropy
+///
+/// QSE = SampEn + ln(2r) (Lake and Moorman 2011) turns the sample entropy
+/// into a density estimate, which makes values obtained with different r comparable.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data for which QSE is to be calculated
+/// * `m`: usize - the template length (embedding dimension)
+/// * `r`: f64 - the radius of comparison
+/// # Returns
+/// * f64 - the quadratic sample entropy
+pub fn calc_qse(signal: &[f64], m: usize, r: f64) -> f64 {
+    calc_samp_en_estimate(signal, m, r).sampen + (2.0 * r).ln()
+}
+
+/// Calculate the Coefficient of Sample Entropy
+///
+/// COSEn = QSE - ln(mean) (Lake and Moorman 2011), developed for detecting
+/// atrial fibrillation on short RR segments. The value does not depend on the
+/// units of the signal, as long as r is given in the same units.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data for which COSEn is to be calculated
+/// * `m`: usize - the template length (embedding dimension)
+/// * `r`: f64 - the radius of comparison
+/// # Returns
+/// * f64 - the coefficient of sample entropy
+pub fn calc_cosen(signal: &[f64], m: usize, r: f64) -> f64 {
+    calc_qse(signal, m, r) - crate::stats::mean(signal).ln()
 }
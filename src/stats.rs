@@ -0,0 +1,165 @@
+// helper statistics shared by the analysis modules
+
+/// Quantile Function of the Standard Normal Distribution
+///
+/// Uses Acklam's rational approximation (relative error below 1.2e-9),
+/// which is more than enough for building confidence intervals.
+///
+/// # Parameters
+/// * `p`: f64 - the probability, must lie in (0, 1)
+/// # Returns
+/// * f64 - z such that P(Z <= z) = p, NaN for p outside (0, 1)
+pub(crate) fn normal_quantile(p: f64) -> f64 {
+    if !(p > 0.0 && p < 1.0) {
+        return f64::NAN;
+    }
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    let p_low = 0.02425;
+    if p < p_low {
+        // lower tail
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - p_low {
+        // central region
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        // upper tail, by symmetry
+        -normal_quantile(1.0 - p)
+    }
+}
+
+// arithmetic mean, NaN for an empty slice
+pub(crate) fn mean(x: &[f64]) -> f64 {
+    x.iter().sum::<f64>() / x.len() as f64
+}
+
+// sample standard deviation (n - 1 in the denominator)
+pub(crate) fn std_dev(x: &[f64]) -> f64 {
+    let mean = mean(x);
+    let sum_sq: f64 = x.iter().map(|v| (v - mean).powi(2)).sum();
+    (sum_sq / (x.len() as f64 - 1.0)).sqrt()
+}
+
+// least-squares line y = slope * x + intercept together with the Pearson correlation
+// of x and y, returned as (slope, intercept, correlation)
+pub(crate) fn linear_regression(x: &[f64], y: &[f64]) -> (f64, f64, f64) {
+    let (mean_x, mean_y) = (mean(x), mean(y));
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for (xi, yi) in x.iter().zip(y) {
+        sxx += (xi - mean_x).powi(2);
+        syy += (yi - mean_y).powi(2);
+        sxy += (xi - mean_x) * (yi - mean_y);
+    }
+    let slope = sxy / sxx;
+    (slope, mean_y - slope * mean_x, sxy / (sxx * syy).sqrt())
+}
+
+// least-squares line y = slope * x + intercept, returned as (slope, intercept)
+pub(crate) fn linear_fit(x: &[f64], y: &[f64]) -> (f64, f64) {
+    let (slope, intercept, _) = linear_regression(x, y);
+    (slope, intercept)
+}
+
+// natural logarithm of the gamma function (Lanczos approximation)
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000_000_000_190_015;
+    for (j, c) in COEFFICIENTS.iter().enumerate() {
+        series += c / (x + 1.0 + j as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+// regularized upper incomplete gamma function Q(a, x), by series or continued fraction
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let ln_prefactor = -x + a * x.ln() - ln_gamma(a);
+    if x < a + 1.0 {
+        // series for P(a, x)
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        let mut denominator = a;
+        for _ in 0..1000 {
+            denominator += 1.0;
+            term *= x / denominator;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        1.0 - sum * ln_prefactor.exp()
+    } else {
+        // Lentz's continued fraction for Q(a, x)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        h * ln_prefactor.exp()
+    }
+}
+
+// survival function of the chi-square distribution, P(X > x)
+pub(crate) fn chi_square_sf(x: f64, degrees_of_freedom: f64) -> f64 {
+    gamma_q(degrees_of_freedom / 2.0, x / 2.0)
+}
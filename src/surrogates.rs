@@ -0,0 +1,360 @@
+use crate::rng::Rng;
+use crate::stats::{mean, std_dev};
+use std::f64::consts::PI;
+
+/// Surrogate Generation Method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurrogateMethod {
+    /// random permutation - keeps the distribution, destroys all temporal structure
+    Shuffle,
+    /// Fourier phases randomised - keeps the power spectrum (linear correlations) of a Gaussian process
+    PhaseRandomized,
+    /// amplitude-adjusted FT (Theiler et al. 1992) - phase randomisation of the Gaussianised series,
+    /// rescaled to the original distribution
+    Aaft,
+    /// iterative AAFT (Schreiber and Schmitz 1996) - alternately imposes the power spectrum and the
+    /// distribution until the ranking settles or max_iterations is reached
+    Iaaft { max_iterations: usize },
+    /// the series reversed in time - keeps everything but the direction of time; it is
+    /// deterministic, so every surrogate is the same series and `surrogate_test` only
+    /// reports the value of the reversed series, without SD, z-score or p-value
+    TimeReversed,
+}
+
+/// Settings of the Surrogate Test
+///
+/// The defaults are 99 IAAFT surrogates of at most 100 iterations each with seed 0,
+/// giving rank p-values down to 0.01.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurrogateConfig {
+    pub method: SurrogateMethod,
+    pub surrogates: usize,
+    pub seed: u64,
+}
+
+impl Default for SurrogateConfig {
+    fn default() -> Self {
+        SurrogateConfig {
+            method: SurrogateMethod::Iaaft {
+                max_iterations: 100,
+            },
+            surrogates: 99,
+            seed: 0,
+        }
+    }
+}
+
+/// Comparison of One Index with Its Surrogate Distribution
+///
+/// For a comparison with the time-reversed series, `mean` is the value of the
+/// reversed series and `sd`, `z_score` and `p_value` are NaN.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurrogateComparison {
+    pub observed: f64,
+    pub mean: f64,
+    pub sd: f64,
+    pub z_score: f64,
+    /// two-sided rank p-value, (1 + surrogates at least as far from their mean) / (surrogates + 1)
+    pub p_value: f64,
+}
+
+impl SurrogateComparison {
+    /// the observed value minus the surrogate mean (or the value of the reversed series)
+    pub fn difference(&self) -> f64 {
+        self.observed - self.mean
+    }
+}
+
+pub(crate) fn compare(observed: f64, surrogates: &[f64]) -> SurrogateComparison {
+    let (mean, sd) = (mean(surrogates), std_dev(surrogates));
+    let departure = (observed - mean).abs();
+    let extreme = surrogates
+        .iter()
+        .filter(|&&s| (s - mean).abs() >= departure)
+        .count();
+    SurrogateComparison {
+        observed,
+        mean,
+        sd,
+        z_score: (observed - mean) / sd,
+        p_value: (1 + extreme) as f64 / (surrogates.len() + 1) as f64,
+    }
+}
+
+// comparison with the single, deterministic time-reversed series, which has no distribution
+pub(crate) fn compare_reversed(observed: f64, reversed: f64) -> SurrogateComparison {
+    SurrogateComparison {
+        observed,
+        mean: reversed,
+        sd: f64::NAN,
+        z_score: f64::NAN,
+        p_value: f64::NAN,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    fn polar(magnitude: f64, angle: f64) -> Self {
+        Complex::new(magnitude * angle.cos(), magnitude * angle.sin())
+    }
+
+    fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    fn conj(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+
+    fn add(self, other: Self) -> Self {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+
+    fn mul(self, other: Self) -> Self {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+// in-place iterative radix-2 FFT, the length has to be a power of two; unnormalised
+fn fft_radix2(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+    while length <= n {
+        let step = Complex::polar(1.0, sign * 2.0 * PI / length as f64);
+        for start in (0..n).step_by(length) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..length / 2 {
+                let even = data[start + k];
+                let odd = data[start + k + length / 2].mul(w);
+                data[start + k] = even.add(odd);
+                data[start + k + length / 2] = even.sub(odd);
+                w = w.mul(step);
+            }
+        }
+        length <<= 1;
+    }
+}
+
+// unnormalised DFT of any length - radix-2 directly, other lengths by Bluestein's chirp-z algorithm
+fn dft(data: &[Complex], inverse: bool) -> Vec<Complex> {
+    let n = data.len();
+    if n.is_power_of_two() || n == 0 {
+        let mut result = data.to_vec();
+        fft_radix2(&mut result, inverse);
+        return result;
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    // the chirp exp(sign i pi k^2 / n), with k^2 reduced modulo 2n to keep the angle accurate
+    let chirp: Vec<Complex> = (0..n)
+        .map(|k| Complex::polar(1.0, sign * PI * ((k * k) % (2 * n)) as f64 / n as f64))
+        .collect();
+    let m = (2 * n - 1).next_power_of_two();
+    let mut a = vec![Complex::new(0.0, 0.0); m];
+    let mut b = vec![Complex::new(0.0, 0.0); m];
+    for k in 0..n {
+        a[k] = data[k].mul(chirp[k]);
+        b[k] = chirp[k].conj();
+        if k > 0 {
+            b[m - k] = chirp[k].conj();
+        }
+    }
+    fft_radix2(&mut a, false);
+    fft_radix2(&mut b, false);
+    let mut convolution: Vec<Complex> = a.iter().zip(&b).map(|(x, y)| x.mul(*y)).collect();
+    fft_radix2(&mut convolution, true);
+    (0..n)
+        .map(|k| {
+            let c = convolution[k];
+            chirp[k].mul(Complex::new(c.re / m as f64, c.im / m as f64))
+        })
+        .collect()
+}
+
+// the real signal with the given Fourier amplitudes and phases
+fn inverse_real(amplitudes: &[f64], phases: &[f64]) -> Vec<f64> {
+    let spectrum: Vec<Complex> = amplitudes
+        .iter()
+        .zip(phases)
+        .map(|(&a, &p)| Complex::polar(a, p))
+        .collect();
+    let n = spectrum.len() as f64;
+    dft(&spectrum, true).iter().map(|c| c.re / n).collect()
+}
+
+fn spectrum(signal: &[f64]) -> Vec<Complex> {
+    let data: Vec<Complex> = signal.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    dft(&data, false)
+}
+
+// random phases for a real signal - the phase of frequency k is minus that of n - k,
+// the mean and the Nyquist frequency keep their phase
+fn random_phases(spectrum: &[Complex], rng: &mut Rng) -> Vec<f64> {
+    let n = spectrum.len();
+    let mut phases: Vec<f64> = spectrum.iter().map(|c| c.im.atan2(c.re)).collect();
+    for k in 1..n.div_ceil(2) {
+        let phase = 2.0 * PI * rng.uniform();
+        phases[k] = phase;
+        phases[n - k] = -phase;
+    }
+    phases
+}
+
+fn phase_randomized(signal: &[f64], rng: &mut Rng) -> Vec<f64> {
+    let spectrum = spectrum(signal);
+    let amplitudes: Vec<f64> = spectrum.iter().map(|c| c.abs()).collect();
+    inverse_real(&amplitudes, &random_phases(&spectrum, rng))
+}
+
+// indices that sort the values
+fn argsort(values: &[f64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    order
+}
+
+// the sorted values arranged in the ranking of `template`
+fn rank_remap(template: &[f64], sorted: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; template.len()];
+    for (rank, i) in argsort(template).into_iter().enumerate() {
+        result[i] = sorted[rank];
+    }
+    result
+}
+
+fn sorted(signal: &[f64]) -> Vec<f64> {
+    let mut sorted = signal.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+// standard normal deviate by the Box-Muller transform
+fn gaussian(rng: &mut Rng) -> f64 {
+    let (u, v) = (1.0 - rng.uniform(), rng.uniform());
+    (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+fn aaft(signal: &[f64], rng: &mut Rng) -> Vec<f64> {
+    let normal = sorted(&(0..signal.len()).map(|_| gaussian(rng)).collect::<Vec<_>>());
+    let gaussianized = rank_remap(signal, &normal);
+    rank_remap(&phase_randomized(&gaussianized, rng), &sorted(signal))
+}
+
+fn iaaft(signal: &[f64], max_iterations: usize, rng: &mut Rng) -> Vec<f64> {
+    let amplitudes: Vec<f64> = spectrum(signal).iter().map(|c| c.abs()).collect();
+    let sorted = sorted(signal);
+    let mut surrogate = signal.to_vec();
+    rng.shuffle(&mut surrogate);
+    let mut ranking = argsort(&surrogate);
+    for _ in 0..max_iterations {
+        let phases: Vec<f64> = spectrum(&surrogate)
+            .iter()
+            .map(|c| c.im.atan2(c.re))
+            .collect();
+        let filtered = inverse_real(&amplitudes, &phases);
+        surrogate = rank_remap(&filtered, &sorted);
+        let new_ranking = argsort(&surrogate);
+        if new_ranking == ranking {
+            break;
+        }
+        ranking = new_ranking;
+    }
+    surrogate
+}
+
+fn surrogate(signal: &[f64], method: SurrogateMethod, rng: &mut Rng) -> Vec<f64> {
+    match method {
+        SurrogateMethod::Shuffle => {
+            let mut surrogate = signal.to_vec();
+            rng.shuffle(&mut surrogate);
+            surrogate
+        }
+        SurrogateMethod::PhaseRandomized => phase_randomized(signal, rng),
+        SurrogateMethod::Aaft => aaft(signal, rng),
+        SurrogateMethod::Iaaft { max_iterations } => iaaft(signal, max_iterations, rng),
+        SurrogateMethod::TimeReversed => signal.iter().rev().copied().collect(),
+    }
+}
+
+/// Generate Surrogate Series
+///
+/// The surrogates are reproducible - the same signal, method and seed always give
+/// the same series. Annotated beats should be removed or corrected beforehand, as
+/// every value takes part in the surrogate.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `config`: &SurrogateConfig - the method, number of surrogates and seed
+/// # Returns
+/// * Vec<Vec<f64>> - the surrogate series, each as long as the signal
+pub fn generate_surrogates(signal: &[f64], config: &SurrogateConfig) -> Vec<Vec<f64>> {
+    let mut rng = Rng::new(config.seed);
+    (0..config.surrogates)
+        .map(|_| surrogate(signal, config.method, &mut rng))
+        .collect()
+}
+
+/// Test a Metric Against Surrogates
+///
+/// This function calculates the metric - e.g. sample entropy, a runs summary
+/// count or an asymmetry index - for the signal and for every surrogate, and
+/// compares the observed value with the surrogate distribution. Surrogates for
+/// which the metric is NaN are left out of the distribution. Time reversal gives a
+/// single series rather than a distribution, so for `TimeReversed` the observed value
+/// is only set against the value of the reversed series, whatever the number of
+/// surrogates.
+///
+/// # Parameters
+/// * `signal`: &[f64] - the data
+/// * `config`: &SurrogateConfig - the method, number of surrogates and seed
+/// * `metric`: FnMut(&[f64]) -> f64 - the statistic to test
+/// # Returns
+/// * SurrogateComparison - the observed value, surrogate mean and SD, z-score and rank p-value
+pub fn surrogate_test<F>(
+    signal: &[f64],
+    config: &SurrogateConfig,
+    mut metric: F,
+) -> SurrogateComparison
+where
+    F: FnMut(&[f64]) -> f64,
+{
+    let observed = metric(signal);
+    if config.method == SurrogateMethod::TimeReversed {
+        let reversed: Vec<f64> = signal.iter().rev().copied().collect();
+        return compare_reversed(observed, metric(&reversed));
+    }
+    let values: Vec<f64> = generate_surrogates(signal, config)
+        .iter()
+        .map(|surrogate| metric(surrogate))
+        .filter(|value| !value.is_nan())
+        .collect();
+    compare(observed, &values)
+}
@@ -0,0 +1,185 @@
+use crate::data_reader::RRSeries;
+use crate::stats::mean;
+use std::cmp::Ordering;
+
+/// Porta Pattern Percentages
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortaPatterns {
+    /// the number of words of 3 normal beats
+    pub words: usize,
+    /// % of words without variation
+    pub zero_v: f64,
+    /// % of words with one variation
+    pub one_v: f64,
+    /// % of words with two like variations (monotonic)
+    pub two_lv: f64,
+    /// % of words with two unlike variations (peaks and valleys)
+    pub two_uv: f64,
+}
+
+/// Settings of the Voss Word Statistics
+///
+/// The defaults follow Voss et al. (1996): symbols split at the mean and at
+/// ±5% of it, words of 3 symbols, Rényi entropies of orders 0.25 and 4, and
+/// words with a probability below 0.001 counted as forbidden.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VossConfig {
+    /// the relative distance of the outer thresholds from the mean
+    pub a: f64,
+    pub word_length: usize,
+    pub renyi_orders: Vec<f64>,
+    pub forbidden_threshold: f64,
+}
+
+impl Default for VossConfig {
+    fn default() -> Self {
+        VossConfig {
+            a: 0.05,
+            word_length: 3,
+            renyi_orders: vec![0.25, 4.0],
+            forbidden_threshold: 0.001,
+        }
+    }
+}
+
+/// Voss Word Statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct VossWords {
+    /// the number of words of normal beats
+    pub words: usize,
+    /// the number of occurrences of each word, indexed by the word read as a base-4 number
+    pub counts: Vec<usize>,
+    /// Shannon entropy of the word distribution in bits
+    pub shannon: f64,
+    /// Rényi entropies in bits, in the order of VossConfig::renyi_orders
+    pub renyi: Vec<f64>,
+    /// the number of words with a probability below the forbidden threshold
+    pub forbidden: usize,
+}
+
+// starting indices of the words of `length` beats without annotated beats
+fn valid_words(series: &RRSeries, length: usize) -> impl Iterator<Item = usize> + '_ {
+    let size = series.rr.len().min(series.annot.len());
+    (0..(size + 1).saturating_sub(length))
+        .filter(move |&i| length > 0 && series.annot[i..i + length].iter().all(|&a| a == 0))
+}
+
+/// Calculate Porta's Symbolic Patterns
+///
+/// This function quantises the normal RR intervals uniformly into `levels` levels
+/// between their minimum and maximum (Porta et al. 2001) and classifies the words
+/// of 3 consecutive symbols by the number and direction of their variations. Words
+/// including an annotated beat are skipped.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `levels`: usize - the number of quantisation levels, 6 in Porta et al.
+/// # Returns
+/// * PortaPatterns - the percentages of 0V, 1V, 2LV and 2UV words
+pub fn porta_patterns(series: &RRSeries, levels: usize) -> PortaPatterns {
+    let normal = series.normal_rr();
+    let min = normal.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = normal.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let width = (max - min) / levels as f64;
+    let symbol = |rr: f64| {
+        if width > 0.0 {
+            (((rr - min) / width) as usize).min(levels.saturating_sub(1))
+        } else {
+            0
+        }
+    };
+    let mut counts = [0usize; 4];
+    for i in valid_words(series, 3) {
+        let symbols = [
+            symbol(series.rr[i]),
+            symbol(series.rr[i + 1]),
+            symbol(series.rr[i + 2]),
+        ];
+        let first = symbols[0].cmp(&symbols[1]);
+        let second = symbols[1].cmp(&symbols[2]);
+        let pattern = match (first, second) {
+            (Ordering::Equal, Ordering::Equal) => 0,
+            (Ordering::Equal, _) | (_, Ordering::Equal) => 1,
+            _ if first == second => 2,
+            _ => 3,
+        };
+        counts[pattern] += 1;
+    }
+    let words: usize = counts.iter().sum();
+    let percentage = |count: usize| 100.0 * count as f64 / words as f64;
+    PortaPatterns {
+        words,
+        zero_v: percentage(counts[0]),
+        one_v: percentage(counts[1]),
+        two_lv: percentage(counts[2]),
+        two_uv: percentage(counts[3]),
+    }
+}
+
+/// Calculate Voss Word Statistics
+///
+/// This function codes the RR intervals with the 4-symbol alphabet of Voss et al.
+/// (1996) - 0 for mean < RR <= (1 + a) mean, 1 above, 2 for (1 - a) mean < RR <= mean
+/// and 3 below, with the mean of the normal beats - and calculates the Shannon and
+/// Rényi entropies of the distribution of words of `word_length` symbols and the
+/// number of forbidden words. Words including an annotated beat are skipped.
+///
+/// # Parameters
+/// * `series`: &RRSeries - the RR intervals with their annotations
+/// * `config`: &VossConfig - the alphabet thresholds, word length and entropy orders
+/// # Returns
+/// * VossWords - the word counts, entropies and the number of forbidden words
+pub fn voss_words(series: &RRSeries, config: &VossConfig) -> VossWords {
+    let mu = mean(&series.normal_rr());
+    let symbol = |rr: f64| {
+        if rr > (1.0 + config.a) * mu {
+            1
+        } else if rr > mu {
+            0
+        } else if rr > (1.0 - config.a) * mu {
+            2
+        } else {
+            3
+        }
+    };
+    let mut counts = vec![0usize; 4usize.pow(config.word_length as u32)];
+    for i in valid_words(series, config.word_length) {
+        let word = series.rr[i..i + config.word_length]
+            .iter()
+            .fold(0, |word, &rr| 4 * word + symbol(rr));
+        counts[word] += 1;
+    }
+    let words: usize = counts.iter().sum();
+    let probabilities: Vec<f64> = counts
+        .iter()
+        .map(|&count| count as f64 / words as f64)
+        .collect();
+    let shannon = -probabilities
+        .iter()
+        .filter(|&&p| p > 0.0)
+        .map(|p| p * p.log2())
+        .sum::<f64>();
+    let renyi = config
+        .renyi_orders
+        .iter()
+        .map(|&order| {
+            let sum: f64 = probabilities
+                .iter()
+                .filter(|&&p| p > 0.0)
+                .map(|p| p.powf(order))
+                .sum();
+            sum.log2() / (1.0 - order)
+        })
+        .collect();
+    let forbidden = probabilities
+        .iter()
+        .filter(|&&p| p < config.forbidden_threshold)
+        .count();
+    VossWords {
+        words,
+        counts,
+        shannon,
+        renyi,
+        forbidden,
+    }
+}
@@ -3,6 +3,28 @@ use std::io;
 use hrvhra_rust::data_reader::RRSeries;
 use hrvhra_rust::runs::RRRuns;
 
+// shared test fixtures
+fn lcg_series(n: usize, seed: u64) -> Vec<f64> {
+    let mut state = seed;
+    (0..n)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            600.0 + ((state >> 11) as f64 / (1u64 << 53) as f64) * 400.0
+        })
+        .collect()
+}
+
+fn series_from(rr: Vec<f64>, annot: Vec<u8>) -> RRSeries {
+    RRSeries {
+        size: rr.len(),
+        annot,
+        column_names: vec!["RR".to_string(), "annot".to_string()],
+        rr,
+    }
+}
+
 // runs integration tests
 #[test]
 fn test_case_1() -> io::Result<()> {
@@ -107,3 +129,1210 @@ fn test_entropy_known_values() -> io::Result<()> {
     assert!(!sampen.is_finite());
     Ok(())
 }
+
+#[test]
+fn test_entropy_estimate_counts() {
+    // templates of length 1 match in 4 pairs, and all of them extend to length 2
+    let signal = vec![1.0, 2.0, 1.0, 2.0, 1.0, 2.0];
+    let estimate = hrvhra_rust::samp_en::calc_samp_en_estimate(&signal, 1, 0.1);
+    assert_eq!(estimate.b, 4);
+    assert_eq!(estimate.a, 4);
+    assert_eq!(estimate.sampen, 0.0);
+    // the matches (0, 2), (0, 4), (2, 4) share a template, 6 ordered pairs (K_B), while all
+    // 12 ordered pairs of matches overlap as templates of length 2 (K_A), so that
+    // var(CP) = (K_A - K_B) / B^2 = 6 / 16
+    assert_eq!(estimate.cp_se, 6f64.sqrt() / 4.0);
+    assert_eq!(estimate.se, 6f64.sqrt() / 4.0);
+}
+
+#[test]
+fn test_entropy_estimate_matches_calc_samp_en() {
+    use hrvhra_rust::samp_en::{calc_samp_en, calc_samp_en_estimate};
+    // calc_samp_en with m = 2 compares templates of length 1 and 2, like the estimate with m = 1;
+    // they differ only by the one extra template of length 1 counted by calc_samp_en
+    let signal = lcg_series(1000, 11);
+    let r = 25.0;
+    let estimate = calc_samp_en_estimate(&signal, 1, r);
+    let sampen = calc_samp_en(&signal, 2, r);
+    assert!((estimate.sampen - sampen).abs() < 0.01);
+    assert!((calc_samp_en_estimate(&signal, 2, r).sampen - sampen).abs() > 0.01);
+}
+
+#[test]
+fn test_entropy_estimate_confidence_interval() -> io::Result<()> {
+    let rr_series = RRSeries::read_rr("tests/data/test2.csv")?;
+    let estimate = hrvhra_rust::samp_en::calc_samp_en_estimate(&rr_series.rr, 1, 1.0);
+    assert!(estimate.a <= estimate.b);
+    assert!(estimate.se.is_finite() && estimate.se >= 0.0);
+    let (lower, upper) = estimate.confidence_interval(0.95);
+    assert!(lower <= estimate.sampen && estimate.sampen <= upper);
+    assert!((upper - estimate.sampen - 1.959964 * estimate.se).abs() < 1e-5);
+    Ok(())
+}
+
+#[test]
+fn test_entropy_tolerance_sd_fraction() -> io::Result<()> {
+    use hrvhra_rust::samp_en::{calc_samp_en_with_tolerance, Tolerance};
+    let rr_series = RRSeries::read_rr("tests/data/test2.csv")?;
+    let estimate = calc_samp_en_with_tolerance(&rr_series.rr, 1, Tolerance::SdFraction(0.2));
+    // the SD of test2 is sqrt(6/7)
+    assert!((estimate.r - 0.2 * (6.0f64 / 7.0).sqrt()).abs() < 1e-12);
+    assert_eq!(estimate.tolerance, Tolerance::SdFraction(0.2));
+    Ok(())
+}
+
+#[test]
+fn test_entropy_tolerance_data_driven() {
+    use hrvhra_rust::samp_en::{calc_ap_en, select_tolerance, Tolerance};
+    let signal: Vec<f64> = (0..200)
+        .map(|i| (i as f64 * 0.7).sin() + 0.3 * (i as f64 * 2.3).cos())
+        .collect();
+    assert_eq!(calc_ap_en(&[1.0; 10], 2, 0.1), 0.0);
+    let r_max = select_tolerance(&signal, 2, Tolerance::MaxApEn);
+    let ap_en_max = calc_ap_en(&signal, 2, r_max);
+    assert!(ap_en_max >= calc_ap_en(&signal, 2, 0.5 * r_max));
+    assert!(ap_en_max >= calc_ap_en(&signal, 2, 2.0 * r_max));
+    assert!(select_tolerance(&signal, 2, Tolerance::MinRelativeError) > 0.0);
+    assert!(select_tolerance(&signal, 2, Tolerance::Chon) > 0.0);
+    assert!(select_tolerance(&signal, 4, Tolerance::Chon).is_nan());
+}
+
+// atrial fibrillation detection tests
+#[test]
+fn test_cosen_is_unit_free() {
+    use hrvhra_rust::samp_en::{calc_cosen, calc_qse};
+    let rr_ms = vec![
+        800.0, 640.0, 910.0, 700.0, 820.0, 600.0, 950.0, 720.0, 810.0, 660.0,
+    ];
+    let rr_s: Vec<f64> = rr_ms.iter().map(|rr| rr / 1000.0).collect();
+    assert!((calc_cosen(&rr_ms, 1, 105.0) - calc_cosen(&rr_s, 1, 0.105)).abs() < 1e-9);
+    assert!((calc_qse(&rr_ms, 1, 105.0) - calc_qse(&rr_s, 1, 0.105) - 1000f64.ln()).abs() < 1e-9);
+}
+
+#[test]
+fn test_af_detection_flags_irregular_segments() {
+    use hrvhra_rust::af_detection::{detect_af, AfDetectionConfig};
+    // 48 beats of sinus rhythm followed by 48 beats of irregular rhythm
+    let mut rr: Vec<f64> = (0..48)
+        .map(|i| 800.0 + 10.0 * (i as f64 * 0.3).sin())
+        .collect();
+    rr.extend(lcg_series(48, 7));
+    let annot = vec![0; rr.len()];
+    let series = series_from(rr, annot);
+    let segments = detect_af(&series, &AfDetectionConfig::default());
+    assert_eq!(segments.len(), 8);
+    assert!(segments[..4].iter().all(|segment| !segment.af));
+    assert!(segments[4..]
+        .iter()
+        .all(|segment| segment.af && segment.probability > 0.5));
+    assert_eq!(segments[1].onset, series.rr[..12].iter().sum::<f64>());
+}
+
+// typed runs tests
+#[test]
+fn test_runs_iterator() -> io::Result<()> {
+    use hrvhra_rust::runs::{Run, RunType};
+    let rr_series = RRSeries::read_rr("tests/data/test1.csv")?;
+    let mut rr = RRRuns::new(rr_series.rr, rr_series.annot, true);
+    let runs: Vec<Run> = rr.runs().map(|run| *run.run()).collect();
+    assert_eq!(
+        runs,
+        vec![
+            Run {
+                start: 0,
+                end: 1,
+                length: 1,
+                kind: RunType::Dec
+            },
+            Run {
+                start: 3,
+                end: 4,
+                length: 1,
+                kind: RunType::Dec
+            },
+            Run {
+                start: 6,
+                end: 7,
+                length: 1,
+                kind: RunType::Acc
+            },
+        ]
+    );
+    let second = rr.runs().nth(1).unwrap();
+    assert_eq!(second.rr_values(), &[1.0, 2.0]);
+    assert_eq!(second.onset(), 10.0);
+    assert_eq!(second.offset(), 12.0);
+    assert_eq!(second.duration(), 2.0);
+    Ok(())
+}
+
+// runs reports tests
+#[test]
+fn test_runs_report_formats() -> io::Result<()> {
+    let rr_series = RRSeries::read_rr("tests/data/test2.csv")?;
+    let mut rr = RRRuns::new(rr_series.rr, rr_series.annot, true);
+    let report = rr.report();
+    assert_eq!(report.summary.rows.len(), 2);
+    assert_eq!(report.runs.runs.len(), 6);
+
+    let mut csv = Vec::new();
+    report.summary.write_csv(&mut csv)?;
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "length,ar,dr,n\n1,2,2,1\n2,0,0,1\n"
+    );
+
+    let neutral = report.runs.filter(hrvhra_rust::runs::RunType::Neu, 2);
+    assert_eq!(
+        neutral.to_json(),
+        "[{\"type\":\"NEU\",\"start\":4,\"end\":6,\"length\":2}]"
+    );
+    assert!(report
+        .to_json()
+        .starts_with("{\"summary\":[{\"length\":1,\"ar\":2,\"dr\":2,\"n\":1}"));
+
+    let table = report.summary.to_string();
+    assert_eq!(table.lines().count(), 3);
+    assert_eq!(
+        table
+            .lines()
+            .nth(2)
+            .unwrap()
+            .split_whitespace()
+            .collect::<Vec<_>>(),
+        ["2", "0", "0", "1"]
+    );
+    Ok(())
+}
+
+// neutral tolerance tests
+#[test]
+fn test_runs_neutral_tolerance() {
+    use hrvhra_rust::runs::{NeutralTolerance, RunsConfig};
+    let rr = vec![800.0, 807.8, 815.6, 815.6, 900.0, 850.0];
+    let annot = vec![0; rr.len()];
+
+    let mut exact = RRRuns::new(rr.clone(), annot.clone(), true);
+    assert_eq!(exact.get_runs_summary(), vec![vec![1, 1, 1], vec![0, 1, 0]]);
+
+    // one 128 Hz sample (7.8 ms) counts as no change
+    let config = RunsConfig {
+        neutral: NeutralTolerance::Absolute(8.0),
+        ..RunsConfig::default()
+    };
+    let mut absolute = RRRuns::with_config(rr.clone(), annot.clone(), config);
+    assert_eq!(
+        absolute.get_runs_summary(),
+        vec![vec![1, 1, 0], vec![0, 0, 0], vec![0, 0, 1]]
+    );
+
+    let config = RunsConfig {
+        neutral: NeutralTolerance::Relative(0.01),
+        ..RunsConfig::default()
+    };
+    let mut relative = RRRuns::with_config(rr, annot, config);
+    assert_eq!(relative.get_runs_summary(), absolute.get_runs_summary());
+}
+
+#[test]
+fn test_runs_neutral_continues_run() -> io::Result<()> {
+    use hrvhra_rust::runs::RunsConfig;
+    // 2 2 4 1 2 2 2 1: the neutral steps after 1 -> 2 extend the deceleration run
+    let rr_series = RRSeries::read_rr("tests/data/test2.csv")?;
+    let config = RunsConfig {
+        neutral_continues_run: true,
+        ..RunsConfig::default()
+    };
+    let mut rr = RRRuns::with_config(rr_series.rr, rr_series.annot, config);
+    assert_eq!(
+        rr.get_runs_summary(),
+        vec![vec![2, 1, 1], vec![0, 0, 0], vec![0, 1, 0]]
+    );
+    Ok(())
+}
+
+// runs significance tests
+#[test]
+fn test_expected_iid_runs() {
+    use hrvhra_rust::runs::expected_iid_runs;
+    assert!((expected_iid_runs(3, 1) - 2.0 / 3.0).abs() < 1e-12);
+    assert!((expected_iid_runs(3, 2) - 1.0 / 6.0).abs() < 1e-12);
+    // the expected total number of runs up and down is (2n - 1) / 3
+    let total: f64 = (1..10).map(|k| 2.0 * expected_iid_runs(10, k)).sum();
+    assert!((total - 19.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_runs_distribution_iid() {
+    use hrvhra_rust::runs::{Representation, RunType, RunsExpectation};
+    let rr = lcg_series(2000, 3);
+    let mut random = RRRuns::new(rr.clone(), vec![0; rr.len()], true);
+    let test = random.test_runs_distribution(RunsExpectation::Iid, 0.05);
+    assert!(test.p_value > 0.001);
+    assert!(test.lengths.iter().all(|cell| cell.kind != RunType::Neu));
+
+    // alternating RR - only runs of length 1
+    let alternating: Vec<f64> = (0..200)
+        .map(|i| if i % 2 == 0 { 800.0 } else { 900.0 })
+        .collect();
+    let mut runs = RRRuns::new(alternating, vec![0; 200], true);
+    let test = runs.test_runs_distribution(RunsExpectation::Iid, 0.05);
+    assert!(test.p_value < 1e-6);
+    let first = &test.lengths[0];
+    assert_eq!(
+        (first.kind, first.length, first.observed),
+        (RunType::Dec, 1, 100)
+    );
+    assert_eq!(first.representation, Representation::Over);
+}
+
+#[test]
+fn test_runs_distribution_shuffled() {
+    use hrvhra_rust::runs::{Representation, RunsExpectation};
+    let rr: Vec<f64> = (0..300)
+        .map(|i| 800.0 + 50.0 * (i as f64 * 0.2).sin())
+        .collect();
+    let mut runs = RRRuns::new(rr, vec![0; 300], true);
+    let expectation = RunsExpectation::Shuffled {
+        surrogates: 99,
+        seed: 11,
+    };
+    let test = runs.test_runs_distribution(expectation, 0.05);
+    assert_eq!(test.p_value, 0.01);
+    assert!(test
+        .lengths
+        .iter()
+        .any(|cell| cell.length == 1 && cell.representation == Representation::Under));
+    assert_eq!(test, runs.test_runs_distribution(expectation, 0.05));
+}
+
+// windowed runs tests
+#[test]
+fn test_windowed_runs() {
+    use hrvhra_rust::runs::WindowSize;
+    let rr: Vec<f64> = (0..12).map(|i| (i % 3 + 1) as f64).collect();
+    let runs = RRRuns::new(rr, vec![0; 12], true);
+
+    let windows = runs.windowed(WindowSize::Beats(3), WindowSize::Beats(3));
+    assert_eq!(windows.len(), 4);
+    assert!(windows
+        .iter()
+        .all(|w| w.longest_dec == 2 && w.longest_acc == 0 && w.mean_run_length == 2.0));
+    assert_eq!(
+        (windows[1].start, windows[1].end, windows[1].onset),
+        (3, 6, 7.0)
+    );
+
+    // overlapping windows of 6 beats, moved by one beat, see the 3 -> 1 accelerations too
+    let windows = runs.windowed(WindowSize::Beats(6), WindowSize::Beats(1));
+    assert_eq!(windows.len(), 7);
+    assert_eq!(windows[0].summary.rows[0].acc, 1);
+
+    // windows of 6 time units, in which the last window would reach beyond the recording
+    let windows = runs.windowed(WindowSize::Time(6.0), WindowSize::Time(6.0));
+    assert_eq!(windows.len(), 3);
+    assert_eq!((windows[2].start, windows[2].end), (6, 9));
+    assert_eq!(windows[2].offset, 18.0);
+}
+
+// runs groups tests
+fn accumulator_for(path: &str) -> io::Result<hrvhra_rust::runs::RunsAccumulator> {
+    let rr_series = RRSeries::read_rr(path)?;
+    let mut rr = RRRuns::new(rr_series.rr, rr_series.annot, true);
+    Ok(rr.get_full_runs().clone())
+}
+
+#[test]
+fn test_runs_accumulator_merge() -> io::Result<()> {
+    use hrvhra_rust::runs::RunType;
+    let first = accumulator_for("tests/data/test1.csv")?;
+    let second = accumulator_for("tests/data/test2.csv")?;
+    let merged = first.clone() + second.clone();
+    assert_eq!(merged.count(RunType::Dec, 1), 4);
+    assert_eq!(merged.count(RunType::Acc, 1), 3);
+    assert_eq!(merged.count(RunType::Neu, 2), 1);
+    assert_eq!(
+        merged.runs().len(),
+        first.runs().len() + second.runs().len()
+    );
+    assert_eq!(merged.proportion(RunType::Dec, 1), 4.0 / 9.0);
+    Ok(())
+}
+
+#[test]
+fn test_runs_group_summary() -> io::Result<()> {
+    use hrvhra_rust::runs::{Normalization, RunType, RunsGroup};
+    let mut group = RunsGroup::new();
+    for path in [
+        "tests/data/test1.csv",
+        "tests/data/test2.csv",
+        "tests/data/test3.csv",
+    ] {
+        group.add_subject(accumulator_for(path)?);
+    }
+    assert_eq!(group.pooled().total(RunType::Dec), 5);
+    let summary = group.summary(Normalization::Counts);
+    assert_eq!(summary.rows.len(), 2);
+    let first = &summary.rows[0];
+    assert!((first.mean_acc - 4.0 / 3.0).abs() < 1e-12);
+    assert!((first.sd_dec - (1.0f64 / 3.0).sqrt()).abs() < 1e-12);
+    assert!((first.subjects_neu - 2.0 / 3.0).abs() < 1e-12);
+    assert_eq!(summary.rows[1].subjects_dec, 0.0);
+    assert!((summary.rows[1].subjects_neu - 2.0 / 3.0).abs() < 1e-12);
+    Ok(())
+}
+
+// boundary policies tests
+#[test]
+fn test_runs_boundary_policies() -> io::Result<()> {
+    use hrvhra_rust::runs::{BoundaryPolicy, RunsConfig, TruncatedRuns};
+    // 2 3 4 1 2 3 4 1 with beats 2 and 5 annotated
+    let rr_series = RRSeries::read_rr("tests/data/test1.csv")?;
+    let config = RunsConfig {
+        boundaries: BoundaryPolicy {
+            start: TruncatedRuns::Censor,
+            end: TruncatedRuns::Drop,
+            artifact: TruncatedRuns::Count,
+        },
+        ..RunsConfig::default()
+    };
+    let mut rr = RRRuns::with_config(rr_series.rr, rr_series.annot, config);
+    assert_eq!(rr.get_runs_summary(), vec![vec![0, 1, 0]]);
+    assert_eq!(rr.censored_summary().rows[0].dec, 1);
+    let report = rr.report();
+    assert_eq!(report.censored.rows.len(), 1);
+    assert!(report
+        .to_json()
+        .ends_with("\"censored\":[{\"length\":1,\"ar\":0,\"dr\":1,\"n\":0}]}"));
+    Ok(())
+}
+
+#[test]
+fn test_runs_boundary_policies_drop_all() -> io::Result<()> {
+    use hrvhra_rust::runs::{BoundaryPolicy, RunsConfig, TruncatedRuns};
+    let rr_series = RRSeries::read_rr("tests/data/test2.csv")?;
+    let config = RunsConfig {
+        boundaries: BoundaryPolicy {
+            start: TruncatedRuns::Drop,
+            end: TruncatedRuns::Drop,
+            artifact: TruncatedRuns::Drop,
+        },
+        ..RunsConfig::default()
+    };
+    let mut rr = RRRuns::with_config(rr_series.rr, rr_series.annot, config);
+    assert_eq!(rr.get_runs_summary(), vec![vec![1, 2, 0], vec![0, 0, 1]]);
+    assert!(rr.censored_summary().rows.is_empty());
+    Ok(())
+}
+
+// streaming runs tests
+#[test]
+fn test_runs_stream_matches_batch() -> io::Result<()> {
+    use hrvhra_rust::runs::{RunsConfig, RunsStream};
+    for path in [
+        "tests/data/test1.csv",
+        "tests/data/test2.csv",
+        "tests/data/test7.csv",
+    ] {
+        let rr_series = RRSeries::read_rr(path)?;
+        let mut stream = RunsStream::new(RunsConfig::default());
+        let mut events = Vec::new();
+        for (&rr, &annot) in rr_series.rr.iter().zip(&rr_series.annot) {
+            events.extend(stream.push(rr, annot));
+        }
+        events.extend(stream.finish());
+        let mut batch = RRRuns::new(rr_series.rr, rr_series.annot, true);
+        assert_eq!(stream.summary(), batch.summary());
+        let batch_runs: Vec<_> = batch.runs().map(|run| *run.run()).collect();
+        assert_eq!(
+            events.iter().map(|event| event.run).collect::<Vec<_>>(),
+            batch_runs
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_runs_stream_events() {
+    use hrvhra_rust::runs::{RunType, RunsConfig, RunsStream, TruncatedRuns};
+    let mut stream = RunsStream::new(RunsConfig::default());
+    assert_eq!(stream.push(800.0, 0), None);
+    assert_eq!(stream.push(810.0, 0), None);
+    assert_eq!(stream.push(820.0, 0), None);
+    assert_eq!(stream.current_run(), Some((RunType::Dec, 2)));
+    // the first acceleration completes the deceleration run
+    let event = stream.push(790.0, 0).unwrap();
+    assert_eq!(
+        (event.run.kind, event.run.start, event.run.end),
+        (RunType::Dec, 0, 2)
+    );
+    assert_eq!(event.status, TruncatedRuns::Count);
+    // an annotated beat completes the acceleration run
+    let event = stream.push(600.0, 1).unwrap();
+    assert_eq!((event.run.kind, event.run.length), (RunType::Acc, 1));
+    assert_eq!(stream.current_run(), None);
+    assert_eq!(stream.beats(), 5);
+    assert_eq!(stream.summary().rows.len(), 2);
+}
+
+#[test]
+fn test_runs_stream_after_finish() {
+    use hrvhra_rust::runs::{RunsConfig, RunsStream};
+    let first = vec![800.0, 810.0, 820.0];
+    let second = vec![500.0, 490.0, 495.0, 505.0, 515.0];
+    let mut stream = RunsStream::new(RunsConfig::default());
+    let mut events = Vec::new();
+    for &rr in &first {
+        events.extend(stream.push(rr, 0));
+    }
+    events.extend(stream.finish());
+    // no step across the gap
+    assert_eq!(stream.push(second[0], 0), None);
+    assert_eq!(stream.current_run(), None);
+    for &rr in &second[1..] {
+        events.extend(stream.push(rr, 0));
+    }
+    events.extend(stream.finish());
+    // the same runs as the batch analysis of the two parts, the second shifted by the first
+    let mut expected = Vec::new();
+    for (part, offset) in [(first.clone(), 0), (second.clone(), first.len())] {
+        let mut batch = RRRuns::new(part.clone(), vec![0; part.len()], true);
+        expected.extend(
+            batch
+                .runs()
+                .map(|run| (run.run().kind, run.run().start + offset, run.run().length)),
+        );
+    }
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| (event.run.kind, event.run.start, event.run.length))
+            .collect::<Vec<_>>(),
+        expected
+    );
+}
+
+// generic series runs tests
+#[test]
+fn test_series_runs_value_types() -> io::Result<()> {
+    use hrvhra_rust::runs::{RunsConfig, SeriesRuns};
+    let rr_series = RRSeries::read_rr("tests/data/test7.csv")?;
+    let mut expected = RRRuns::with_config(
+        rr_series.rr.clone(),
+        rr_series.annot.clone(),
+        RunsConfig::default(),
+    );
+    // the same intervals as f32 and as sample counts at 1 kHz
+    let single: Vec<f32> = rr_series.rr.iter().map(|&rr| rr as f32).collect();
+    let samples: Vec<u32> = rr_series.rr.iter().map(|&rr| rr.round() as u32).collect();
+    let mut single =
+        SeriesRuns::from_values(single, Some(rr_series.annot.clone()), RunsConfig::default());
+    let mut samples =
+        SeriesRuns::from_values(samples, Some(rr_series.annot), RunsConfig::default());
+    assert_eq!(single.summary(), expected.summary());
+    assert_eq!(samples.summary(), expected.summary());
+    assert_eq!(samples.runs_table(), expected.runs_table());
+    let onsets: Vec<f64> = samples.runs().map(|run| run.onset()).collect();
+    let expected_onsets: Vec<f64> = expected.runs().map(|run| run.onset()).collect();
+    assert_eq!(onsets, expected_onsets);
+    Ok(())
+}
+
+#[test]
+fn test_series_runs_mask() {
+    use hrvhra_rust::runs::{RunType, RunsConfig, RunsStream, SeriesRuns};
+    let values: Vec<i32> = vec![120, 125, 130, 128, 126, 131, 135];
+    // without a mask all values are used
+    let mut runs = SeriesRuns::from_values(values.clone(), None, RunsConfig::default());
+    assert_eq!(runs.get_runs_summary(), vec![vec![0, 0, 0], vec![1, 2, 0]]);
+    // a masked value breaks the runs like an annotated beat
+    let mut runs = SeriesRuns::from_values(
+        values,
+        Some(vec![0, 0, 0, 0, 1, 0, 0]),
+        RunsConfig::default(),
+    );
+    assert_eq!(runs.get_runs_summary(), vec![vec![1, 1, 0], vec![0, 1, 0]]);
+    // NaN values cannot be ordered and break the runs as well
+    let mut stream = RunsStream::new(RunsConfig::default());
+    for value in [1.0f32, 2.0, 3.0, f32::NAN, 2.0, 1.0] {
+        stream.push(value, 0);
+    }
+    stream.finish();
+    assert_eq!(stream.accumulator().count(RunType::Dec, 2), 1);
+    assert_eq!(stream.accumulator().count(RunType::Acc, 1), 1);
+    assert_eq!(stream.accumulator().total(RunType::Neu), 0);
+}
+
+// baroreflex sequence method tests
+#[test]
+fn test_baroreflex_sequences() {
+    use hrvhra_rust::baroreflex::{baroreflex_sequences, BaroreflexConfig, SequenceType};
+    // SBP rises for 4 beats with RR following at 5 ms/mmHg, then falls for 3 beats at 10 ms/mmHg;
+    // the last ramp of SBP is not followed by RR
+    let sbp = [
+        120.0, 122.0, 124.0, 126.0, 125.0, 123.0, 121.0, 122.0, 124.0, 126.0,
+    ];
+    let rr = [
+        800.0, 810.0, 820.0, 830.0, 820.0, 800.0, 780.0, 779.0, 780.0, 779.0,
+    ];
+    let result = baroreflex_sequences(&sbp, &rr, None, &BaroreflexConfig::default());
+    assert_eq!(result.up.len(), 1);
+    assert_eq!((result.up[0].start, result.up[0].end), (0, 3));
+    assert!((result.up[0].slope - 5.0).abs() < 1e-9);
+    assert_eq!(result.down.len(), 1);
+    assert_eq!(result.down[0].kind, SequenceType::Down);
+    assert_eq!((result.down[0].start, result.down[0].end), (4, 6));
+    assert!((result.down[0].slope - 10.0).abs() < 1e-9);
+    assert!((result.brs - 7.5).abs() < 1e-9);
+    // three SBP ramps (0-3, 3-6, 6-9), of which two are baroreflex sequences
+    assert_eq!(result.sbp_ramps, 3);
+    assert!((result.bei - 2.0 / 3.0).abs() < 1e-9);
+
+    // with RR lagging by one beat the same pattern is found one RR interval later
+    let mut lagged = vec![800.0];
+    lagged.extend_from_slice(&rr);
+    let config = BaroreflexConfig {
+        lag: 1,
+        ..BaroreflexConfig::default()
+    };
+    let result_lagged = baroreflex_sequences(&sbp, &lagged, None, &config);
+    assert_eq!(result_lagged, result);
+
+    // an annotated beat in the middle of the up-sequence leaves it too short
+    let mut annotations = [0u8; 10];
+    annotations[2] = 1;
+    let result = baroreflex_sequences(&sbp, &rr, Some(&annotations), &BaroreflexConfig::default());
+    assert!(result.up.is_empty());
+    assert_eq!(result.down.len(), 1);
+
+    // one long SBP ramp holding two up-sequences counts once
+    let sbp: Vec<f64> = (0..8).map(|i| 120.0 + 2.0 * i as f64).collect();
+    let rr = [800.0, 810.0, 820.0, 830.0, 820.0, 830.0, 840.0, 850.0];
+    let result = baroreflex_sequences(&sbp, &rr, None, &BaroreflexConfig::default());
+    assert_eq!(result.up.len(), 2);
+    assert_eq!(result.sbp_ramps, 1);
+    assert!(result.bei <= 1.0);
+    assert_eq!(result.bei, 1.0);
+}
+
+// runs descriptors tests
+#[test]
+fn test_runs_descriptors() -> io::Result<()> {
+    let rr_series = RRSeries::read_rr("tests/data/test2.csv")?;
+    let mut rr = RRRuns::new(rr_series.rr, rr_series.annot, true);
+    // two acceleration and two deceleration runs of length 1, neutral runs of lengths 1 and 2
+    let descriptors = rr.descriptors(2);
+    assert_eq!(descriptors.dec.runs, 2);
+    assert_eq!(descriptors.dec.entropy, 0.0);
+    assert_eq!(descriptors.dec.longest, 1);
+    assert!((descriptors.neu.entropy - 2f64.ln()).abs() < 1e-12);
+    assert!((descriptors.neu.mean_length - 1.5).abs() < 1e-12);
+    assert_eq!(descriptors.neu.beats, 3);
+    assert!((descriptors.neu.long_fraction - 2.0 / 7.0).abs() < 1e-12);
+    assert!((descriptors.long_fraction - 2.0 / 7.0).abs() < 1e-12);
+    assert_eq!(descriptors.asymmetry, vec![1.0]);
+    assert_eq!(rr.get_full_runs().descriptors(1).long_fraction, 1.0);
+    Ok(())
+}
+
+// multiscale runs tests
+#[test]
+fn test_coarse_grain() {
+    use hrvhra_rust::coarse_grain::{coarse_grain, coarse_grain_annotations};
+    let values = [1.0, 3.0, 2.0, 4.0, 6.0, 8.0, 5.0];
+    assert_eq!(coarse_grain(&values, 1), values.to_vec());
+    assert_eq!(coarse_grain(&values, 2), vec![2.0, 3.0, 7.0]);
+    assert_eq!(coarse_grain(&values, 3), vec![2.0, 6.0]);
+    assert_eq!(
+        coarse_grain_annotations(&[0, 0, 0, 2, 0, 1, 1], 2),
+        vec![0, 2, 1]
+    );
+}
+
+#[test]
+fn test_runs_multiscale() -> io::Result<()> {
+    use hrvhra_rust::runs::RunType;
+    let rr_series = RRSeries::read_rr("tests/data/test7.csv")?;
+    let mut rr = RRRuns::new(rr_series.rr.clone(), rr_series.annot.clone(), true);
+    let scales = rr.multiscale(3);
+    assert_eq!(scales.len(), 3);
+    assert_eq!(scales[0].scale, 1);
+    assert_eq!(scales[0].summary(), rr.summary());
+
+    // alternating intervals become a monotone series at scale 2, masked pairs break the run
+    let values = vec![
+        800.0, 900.0, 810.0, 910.0, 820.0, 920.0, 830.0, 930.0, 840.0, 940.0,
+    ];
+    let mut runs = RRRuns::new(values.clone(), vec![0; 10], true);
+    assert_eq!(runs.summary().rows.len(), 1);
+    let scales = runs.multiscale(2);
+    assert_eq!(scales[1].accumulator.count(RunType::Dec, 4), 1);
+    let mut annotations = vec![0; 10];
+    annotations[5] = 1;
+    let scales = RRRuns::new(values, annotations, true).multiscale(2);
+    assert_eq!(scales[1].accumulator.count(RunType::Dec, 1), 2);
+    assert_eq!(scales[1].accumulator.total(RunType::Dec), 2);
+    Ok(())
+}
+
+// phase-rectified signal averaging tests
+#[test]
+fn test_prsa_capacities() {
+    use hrvhra_rust::prsa::{calc_prsa, PrsaConfig};
+    let rr: Vec<f64> = (0..30)
+        .map(|i| if i % 3 == 2 { 1040.0 } else { 1000.0 })
+        .collect();
+    let mut series = series_from(rr, vec![0; 30]);
+    let config = PrsaConfig {
+        window: 2,
+        ..PrsaConfig::default()
+    };
+    let prsa = calc_prsa(&series, &config);
+    assert_eq!((prsa.dc.anchors, prsa.ac.anchors), (9, 9));
+    assert_eq!(prsa.dc.curve, vec![1000.0, 1000.0, 1040.0, 1000.0]);
+    assert_eq!(prsa.dc.capacity, 10.0);
+    assert_eq!(prsa.ac.capacity, -10.0);
+
+    // changes of 4% are ignored with a 3% limit
+    let strict = PrsaConfig {
+        max_change: 0.03,
+        ..config.clone()
+    };
+    let prsa = calc_prsa(&series, &strict);
+    assert_eq!(prsa.dc.anchors, 0);
+    assert!(prsa.dc.capacity.is_nan());
+
+    // without a window there is no curve to average
+    let empty = PrsaConfig {
+        window: 0,
+        ..PrsaConfig::default()
+    };
+    let prsa = calc_prsa(&series, &empty);
+    assert_eq!(prsa.dc.anchors, 10);
+    assert!(prsa.dc.curve.is_empty());
+    assert!(prsa.dc.capacity.is_nan() && prsa.ac.capacity.is_nan());
+
+    // anchors with an annotated beat in their window are skipped
+    series.annot[10] = 1;
+    let prsa = calc_prsa(&series, &config);
+    assert_eq!((prsa.dc.anchors, prsa.ac.anchors), (8, 7));
+    assert_eq!(prsa.dc.capacity, 10.0);
+}
+
+// heart rate turbulence tests
+#[test]
+fn test_heart_rate_turbulence() {
+    use hrvhra_rust::hrt::{calc_hrt, HrtConfig};
+    let mut rr = Vec::new();
+    let mut annot = Vec::new();
+    // 10 sinus beats, a VPB with its pause, and 15 sinus beats recovering by `step` ms per beat
+    let mut add_event = |coupling: f64, step: f64, second_vpb: bool| {
+        rr.extend([800.0; 10]);
+        annot.extend([0; 10]);
+        rr.extend([coupling, 1100.0]);
+        annot.extend([1, if second_vpb { 1 } else { 0 }]);
+        rr.extend((0..15).map(|i| 780.0 + step * i as f64));
+        annot.extend([0; 15]);
+    };
+    add_event(560.0, 10.0, false);
+    add_event(700.0, 10.0, false); // not premature enough
+    add_event(600.0, 5.0, false);
+    add_event(560.0, 10.0, true); // couplet, not an isolated VPB
+    let series = series_from(rr, annot);
+    let hrt = calc_hrt(&series, &HrtConfig::default());
+    assert_eq!(hrt.events.len(), 2);
+    assert_eq!(hrt.events[0].index, 10);
+    assert_eq!(hrt.events[1].index, 64);
+    // TO = ((780 + 785) - (800 + 800)) / 1600 for the second event
+    assert!((hrt.events[0].onset - (-30.0 / 16.0)).abs() < 1e-12);
+    assert!((hrt.events[1].onset - (-35.0 / 16.0)).abs() < 1e-12);
+    assert!((hrt.events[0].slope - 10.0).abs() < 1e-9);
+    assert!((hrt.events[1].slope - 5.0).abs() < 1e-9);
+    assert!((hrt.onset - (-65.0 / 32.0)).abs() < 1e-12);
+    assert!((hrt.slope - 7.5).abs() < 1e-9);
+    assert_eq!(hrt.tachogram.len(), 22);
+    assert_eq!(hrt.tachogram[5], 580.0);
+}
+
+// heart rate fragmentation tests
+#[test]
+fn test_fragmentation_indices() {
+    use hrvhra_rust::runs::{NeutralTolerance, RunsConfig};
+    // steps: + - + - + | - - - | + +, i.e. an alternation of 5 single runs,
+    // an acceleration run of 3 steps and a deceleration run of 2 steps
+    let rr = vec![
+        800.0, 810.0, 800.0, 810.0, 800.0, 810.0, 800.0, 790.0, 780.0, 790.0, 800.0,
+    ];
+    let runs = RRRuns::new(rr.clone(), vec![0; rr.len()], false);
+    let fragmentation = runs.fragmentation();
+    assert_eq!(fragmentation.steps, 10);
+    assert_eq!(fragmentation.inflections, 6);
+    assert!((fragmentation.pip - 60.0).abs() < 1e-12);
+    assert!((fragmentation.ials - 0.7).abs() < 1e-12);
+    assert!((fragmentation.pss - 70.0).abs() < 1e-12);
+    assert!((fragmentation.pas - 50.0).abs() < 1e-12);
+
+    // an annotated beat splits the alternation, leaving chains too short to count
+    let mut annotations = vec![0; rr.len()];
+    annotations[2] = 1;
+    let fragmentation = RRRuns::new(rr.clone(), annotations, true).fragmentation();
+    assert_eq!(fragmentation.steps, 8);
+    assert_eq!(fragmentation.pas, 0.0);
+
+    // with a neutral tolerance of 10 ms the alternation becomes a single neutral run
+    let config = RunsConfig {
+        neutral: NeutralTolerance::Absolute(10.0),
+        ..RunsConfig::default()
+    };
+    let fragmentation = RRRuns::with_config(rr, vec![0; 11], config).fragmentation();
+    assert_eq!(fragmentation.inflections, 0);
+    assert_eq!(fragmentation.pas, 0.0);
+}
+
+// symbolic dynamics tests
+#[test]
+fn test_porta_patterns() {
+    use hrvhra_rust::symbolic::porta_patterns;
+    // symbols 0 2 4 2 2 2 5 - words 2LV, 2UV, 1V, 0V, 1V
+    let rr = vec![800.0, 820.0, 840.0, 820.0, 820.0, 820.0, 860.0];
+    let patterns = porta_patterns(&series_from(rr.clone(), vec![0; 7]), 6);
+    assert_eq!(patterns.words, 5);
+    assert_eq!(
+        (
+            patterns.zero_v,
+            patterns.one_v,
+            patterns.two_lv,
+            patterns.two_uv
+        ),
+        (20.0, 40.0, 20.0, 20.0)
+    );
+    // words including the annotated beat are skipped
+    let patterns = porta_patterns(&series_from(rr, vec![0, 0, 0, 1, 0, 0, 0]), 6);
+    assert_eq!(patterns.words, 2);
+    assert_eq!((patterns.one_v, patterns.two_lv), (50.0, 50.0));
+}
+
+#[test]
+fn test_voss_words() {
+    use hrvhra_rust::symbolic::{voss_words, VossConfig};
+    let config = VossConfig::default();
+    // alternating intervals far from the mean give the words 131 and 313 only
+    let rr: Vec<f64> = (0..20)
+        .map(|i| if i % 2 == 0 { 1000.0 } else { 800.0 })
+        .collect();
+    let words = voss_words(&series_from(rr, vec![0; 20]), &config);
+    assert_eq!(words.words, 18);
+    assert_eq!((words.counts[29], words.counts[55]), (9, 9));
+    assert!((words.shannon - 1.0).abs() < 1e-12);
+    assert!(words.renyi.iter().all(|h| (h - 1.0).abs() < 1e-12));
+    assert_eq!(words.forbidden, 62);
+    // a constant series gives a single word
+    let words = voss_words(&series_from(vec![900.0; 10], vec![0; 10]), &config);
+    assert_eq!(words.counts[42], 8);
+    assert_eq!(words.shannon, 0.0);
+    assert_eq!(words.forbidden, 63);
+}
+
+// time irreversibility tests
+#[test]
+fn test_irreversibility_indices() {
+    use hrvhra_rust::irreversibility::{irreversibility, multiscale_irreversibility};
+    // a sawtooth - nine slow decelerations of 5 ms and one fast acceleration of 45 ms
+    let rr: Vec<f64> = (0..200).map(|i| 800.0 + 5.0 * (i % 10) as f64).collect();
+    let indices = irreversibility(&series_from(rr.clone(), vec![0; 200]), 1);
+    assert_eq!(indices.increments, 199);
+    assert!((indices.asymmetry - (19.0 - 180.0) / 199.0).abs() < 1e-12);
+    assert!((indices.porta - 100.0 * 19.0 / 199.0).abs() < 1e-12);
+    assert!((indices.guzik - 100.0 * 180.0 * 25.0 / (180.0 * 25.0 + 19.0 * 2025.0)).abs() < 1e-9);
+    // the time-reversed series has the opposite asymmetry
+    let reversed: Vec<f64> = rr.iter().rev().copied().collect();
+    let forward = multiscale_irreversibility(&series_from(rr, vec![0; 200]), 3);
+    let backward = multiscale_irreversibility(&series_from(reversed, vec![0; 200]), 3);
+    assert_eq!(forward.scales.len(), 3);
+    assert!(forward.asymmetry_index < 0.0);
+    assert!((forward.asymmetry_index + backward.asymmetry_index).abs() < 1e-12);
+    assert!((forward.porta_index + backward.porta_index).abs() < 1e-9);
+}
+
+#[test]
+fn test_irreversibility_surrogates() {
+    use hrvhra_rust::irreversibility::{test_irreversibility, IrreversibilityNull};
+    let rr: Vec<f64> = (0..200).map(|i| 800.0 + 5.0 * (i % 10) as f64).collect();
+    let series = series_from(rr, vec![0; 200]);
+    for null in [
+        IrreversibilityNull::Shuffled {
+            surrogates: 99,
+            seed: 1,
+        },
+        IrreversibilityNull::SignFlipped {
+            surrogates: 99,
+            seed: 1,
+        },
+    ] {
+        let test = test_irreversibility(&series, 3, null);
+        assert_eq!(test.asymmetry_index.p_value, 0.01);
+        assert!(test.asymmetry_index.z_score < -3.0);
+        assert_eq!(test.porta_index.p_value, 0.01);
+        assert_eq!(test, test_irreversibility(&series, 3, null));
+    }
+    // a series without a preferred direction is not flagged
+    let symmetric: Vec<f64> = lcg_series(200, 3);
+    let test = test_irreversibility(
+        &series_from(symmetric, vec![0; 200]),
+        3,
+        IrreversibilityNull::SignFlipped {
+            surrogates: 99,
+            seed: 1,
+        },
+    );
+    assert!(test.asymmetry_index.p_value > 0.05);
+
+    // all three indices change sign when the series is reversed with its annotations
+    let rr: Vec<f64> = (0..120).map(|i| 800.0 + 5.0 * (i % 10) as f64).collect();
+    let mut annot = vec![0; 120];
+    annot[30] = 1;
+    let test = test_irreversibility(
+        &series_from(rr, annot),
+        3,
+        IrreversibilityNull::TimeReversed,
+    );
+    for index in [test.asymmetry_index, test.porta_index, test.guzik_index] {
+        assert!(index.observed.abs() > 1.0);
+        assert!((index.mean + index.observed).abs() < 1e-9);
+        assert!((index.difference() - 2.0 * index.observed).abs() < 1e-9);
+        assert!(index.sd.is_nan() && index.z_score.is_nan() && index.p_value.is_nan());
+    }
+}
+
+// recurrence quantification analysis tests
+#[test]
+fn test_rqa_periodic_series() {
+    use hrvhra_rust::rqa::{calc_rqa, RecurrenceMatrix, RqaConfig};
+    use hrvhra_rust::samp_en::Tolerance;
+    let rr: Vec<f64> = (0..12).map(|i| (1 + i % 4) as f64).collect();
+    let config = RqaConfig {
+        m: 1,
+        tolerance: Tolerance::Absolute(0.5),
+        ..RqaConfig::default()
+    };
+    let rqa = calc_rqa(&series_from(rr.clone(), vec![0; 12]), &config);
+    // every beat recurs 4 and 8 beats away
+    assert!((rqa.recurrence_rate - 24.0 / 132.0).abs() < 1e-12);
+    assert_eq!(rqa.determinism, 1.0);
+    assert_eq!(rqa.mean_line, 6.0);
+    assert_eq!(rqa.max_line, 8);
+    assert!((rqa.entropy - 2f64.ln()).abs() < 1e-12);
+    assert_eq!(rqa.laminarity, 0.0);
+    assert!(rqa.trapping_time.is_nan());
+
+    // an annotated beat takes its embedding vector out of the matrix
+    let mut annot = vec![0; 12];
+    annot[0] = 1;
+    let matrix = RecurrenceMatrix::new(&series_from(rr, annot), 1, 1, 0.5, 1);
+    assert_eq!(matrix.recurrences(), 20);
+    assert!(!matrix.is_recurrent(0, 4));
+    assert_eq!(matrix.row(4), &[8]);
+}
+
+#[test]
+fn test_rqa_laminar_states() {
+    use hrvhra_rust::rqa::RecurrenceMatrix;
+    let rr = vec![1.0, 1.0, 1.0, 1.0, 5.0, 6.0, 7.0, 8.0];
+    let matrix = RecurrenceMatrix::new(&series_from(rr, vec![0; 8]), 1, 1, 0.5, 1);
+    assert_eq!(matrix.size(), 8);
+    let rqa = matrix.quantify(2, 2);
+    assert!((rqa.laminarity - 10.0 / 12.0).abs() < 1e-12);
+    assert_eq!(rqa.trapping_time, 2.5);
+    assert!((rqa.determinism - 5.0 / 6.0).abs() < 1e-12);
+    assert_eq!(rqa.max_line, 3);
+}
+
+#[test]
+fn test_rqa_defaults_stay_sparse() {
+    use hrvhra_rust::rqa::{calc_rqa, RecurrenceMatrix, RqaConfig};
+    // with the max norm, the default radius leaves white noise almost free of recurrences
+    let noise = series_from(lcg_series(300, 19), vec![0; 300]);
+    let rqa = calc_rqa(&noise, &RqaConfig::default());
+    assert!(rqa.recurrence_rate < 0.01);
+    let matrix = RecurrenceMatrix::new(&noise, 10, 1, rqa.r, 1);
+    assert!(matrix.recurrences() < matrix.size());
+}
+
+// fractal measures tests
+#[test]
+fn test_fractal_dimensions() {
+    use hrvhra_rust::fractal::{calc_higuchi_fd, calc_katz_fd};
+    let line: Vec<f64> = (0..100).map(|i| i as f64).collect();
+    let higuchi = calc_higuchi_fd(&line, 8);
+    assert_eq!(higuchi.scales, (1..=8).collect::<Vec<_>>());
+    assert!((higuchi.estimate - 1.0).abs() < 1e-9);
+    assert!(higuchi
+        .fitted()
+        .iter()
+        .zip(&higuchi.log_y)
+        .all(|(fit, y)| (fit - y).abs() < 1e-9));
+    assert!((calc_katz_fd(&line) - 1.0).abs() < 1e-12);
+
+    // white noise fills the plane
+    let noise = lcg_series(2000, 5);
+    let higuchi = calc_higuchi_fd(&noise, 10);
+    assert!(higuchi.estimate > 1.9 && higuchi.estimate < 2.1);
+    assert!(calc_katz_fd(&noise) > calc_katz_fd(&line));
+}
+
+#[test]
+fn test_hurst_exponent() {
+    use hrvhra_rust::fractal::{calc_hurst_aggvar, calc_hurst_rs};
+    let noise = lcg_series(4096, 11);
+    let rs = calc_hurst_rs(&noise, 8);
+    assert_eq!(rs.scales, vec![8, 16, 32, 64, 128, 256, 512, 1024, 2048]);
+    assert!(rs.estimate > 0.4 && rs.estimate < 0.65);
+    let aggvar = calc_hurst_aggvar(&noise, 10);
+    assert_eq!(aggvar.scales.last(), Some(&256));
+    assert!((aggvar.estimate - 0.5).abs() < 0.1);
+
+    // a random walk is strongly persistent
+    let walk: Vec<f64> = noise
+        .iter()
+        .scan(0.0, |sum, x| {
+            *sum += x - 800.0;
+            Some(*sum)
+        })
+        .collect();
+    assert!(calc_hurst_aggvar(&walk, 10).estimate > 0.9);
+    assert!(calc_hurst_rs(&walk, 8).estimate > 0.9);
+}
+
+// correlation dimension and Lyapunov exponent tests
+#[test]
+fn test_correlation_dimension() {
+    use hrvhra_rust::chaos::{
+        calc_correlation_dimension, correlation_integral, CorrelationDimensionConfig,
+    };
+    // of the 15 pairs, 2 are identical and 7 more differ by 1
+    let signal = [0.0, 1.0, 2.0, 3.0, 0.0, 1.0];
+    assert_eq!(
+        correlation_integral(&signal, 1, 1, &[0.5, 1.0, 3.0], 1),
+        vec![2.0 / 15.0, 9.0 / 15.0, 1.0]
+    );
+    // a closed curve is one-dimensional whatever the embedding
+    let sine: Vec<f64> = (0..1000).map(|i| (i as f64 * 0.1).sin()).collect();
+    let config = CorrelationDimensionConfig {
+        embeddings: vec![2, 3, 4],
+        ..CorrelationDimensionConfig::default()
+    };
+    let d2 = calc_correlation_dimension(&sine, &config);
+    assert_eq!(d2.fits.len(), 3);
+    assert!(d2.fits.iter().all(|fit| fit.plateau.is_some()));
+    assert!((d2.d2 - 1.0).abs() < 0.15);
+    // white noise fills the embedding space
+    let noise = lcg_series(1000, 17);
+    let config = CorrelationDimensionConfig {
+        embeddings: vec![1, 2],
+        ..CorrelationDimensionConfig::default()
+    };
+    let d2 = calc_correlation_dimension(&noise, &config);
+    assert!((d2.fits[0].d2 - 1.0).abs() < 0.25);
+    assert!((d2.d2 - 2.0).abs() < 0.4);
+}
+
+#[test]
+fn test_lyapunov_rosenstein() {
+    use hrvhra_rust::chaos::{calc_lyapunov_rosenstein, LyapunovConfig};
+    // the logistic map at r = 4 has the exponent ln 2
+    let logistic: Vec<f64> = std::iter::successors(Some(0.3f64), |x| Some(4.0 * x * (1.0 - x)))
+        .take(1000)
+        .collect();
+    let config = LyapunovConfig {
+        m: 1,
+        theiler: 1,
+        fit_end: 4,
+        ..LyapunovConfig::default()
+    };
+    let fit = calc_lyapunov_rosenstein(&logistic, &config);
+    assert_eq!(fit.divergence.len(), 21);
+    assert!((fit.lambda - 2f64.ln()).abs() < 0.15);
+    // a periodic signal does not diverge
+    let sine: Vec<f64> = (0..1000).map(|i| (i as f64 * 0.1).sin()).collect();
+    let fit = calc_lyapunov_rosenstein(
+        &sine,
+        &LyapunovConfig {
+            m: 3,
+            ..LyapunovConfig::default()
+        },
+    );
+    assert!(fit.lambda.abs() < 0.05);
+}
+
+// Lempel-Ziv complexity tests
+#[test]
+fn test_lempel_ziv() {
+    use hrvhra_rust::lempel_ziv::{binarize, calc_lempel_ziv, Binarization};
+    use hrvhra_rust::runs::RunsConfig;
+    // the example of Kaspar and Schuster, 0.001.10.100.1000.101, parsed by LZ78 as
+    // 0.00.1.10.100.1000.101
+    let bits = "0001101001000101";
+    let signal: Vec<f64> = bits.bytes().map(|b| f64::from(b - b'0') + 1.0).collect();
+    let lz = calc_lempel_ziv(&signal, Binarization::Median);
+    assert_eq!((lz.n, lz.lz76, lz.lz78), (16, 6, 7));
+    assert!((lz.lz76_normalized - 1.5).abs() < 1e-12);
+    // the same sequence as the decelerations of a series
+    let mut rr = vec![800.0];
+    for b in bits.bytes() {
+        let last = *rr.last().unwrap();
+        rr.push(if b == b'1' { last + 10.0 } else { last - 10.0 });
+    }
+    let coding = Binarization::DifferenceSign(RunsConfig::default());
+    let expected: Vec<u8> = bits.bytes().map(|b| b - b'0').collect();
+    assert_eq!(binarize(&rr, coding), expected);
+    assert_eq!(calc_lempel_ziv(&rr, coding).lz76, 6);
+    // neutral steps code as accelerations
+    assert_eq!(
+        binarize(&[800.0, 800.0, 810.0, 805.0], coding),
+        vec![0, 1, 0]
+    );
+}
+
+#[test]
+fn test_multiscale_lempel_ziv() {
+    use hrvhra_rust::lempel_ziv::{calc_lempel_ziv, calc_multiscale_lempel_ziv, Binarization};
+    // white noise is close to maximal complexity, a periodic series far from it
+    let noise = lcg_series(2000, 5);
+    let lz = calc_lempel_ziv(&noise, Binarization::Median);
+    assert!((lz.lz76_normalized - 1.0).abs() < 0.15);
+    let periodic: Vec<f64> = (0..2000).map(|i| (i % 8) as f64).collect();
+    assert!(calc_lempel_ziv(&periodic, Binarization::Median).lz76_normalized < 0.1);
+    let scales = calc_multiscale_lempel_ziv(&noise, Binarization::Median, 4);
+    assert_eq!(scales.len(), 4);
+    assert_eq!(scales[0], lz);
+    assert_eq!(
+        scales.iter().map(|s| (s.scale, s.n)).collect::<Vec<_>>(),
+        vec![(1, 2000), (2, 1000), (3, 666), (4, 500)]
+    );
+    // coarse-grained noise stays random
+    assert!(scales.iter().all(|s| (s.lz76_normalized - 1.0).abs() < 0.2));
+}
+
+// surrogate data tests
+#[test]
+fn test_surrogate_generators() {
+    use hrvhra_rust::surrogates::{generate_surrogates, SurrogateConfig, SurrogateMethod};
+    let signal = lcg_series(1000, 23);
+    let sorted = |x: &[f64]| {
+        let mut x = x.to_vec();
+        x.sort_by(f64::total_cmp);
+        x
+    };
+    let moments = |x: &[f64]| {
+        let mean = x.iter().sum::<f64>() / x.len() as f64;
+        let var = x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / x.len() as f64;
+        (mean, var)
+    };
+    let methods = [
+        SurrogateMethod::Shuffle,
+        SurrogateMethod::PhaseRandomized,
+        SurrogateMethod::Aaft,
+        SurrogateMethod::Iaaft { max_iterations: 50 },
+        SurrogateMethod::TimeReversed,
+    ];
+    for method in methods {
+        let config = SurrogateConfig {
+            method,
+            surrogates: 3,
+            seed: 7,
+        };
+        let surrogates = generate_surrogates(&signal, &config);
+        assert_eq!(surrogates.len(), 3);
+        assert!(surrogates
+            .iter()
+            .all(|s| s.len() == signal.len() && *s != signal));
+        // reproducible with the seed
+        assert_eq!(surrogates, generate_surrogates(&signal, &config));
+        match method {
+            SurrogateMethod::PhaseRandomized => {
+                // the mean and, by Parseval's theorem, the variance are kept
+                let ((m0, v0), (m1, v1)) = (moments(&signal), moments(&surrogates[0]));
+                assert!((m0 - m1).abs() < 1e-6 && (v0 - v1).abs() < 1e-6 * v0);
+            }
+            SurrogateMethod::TimeReversed => {
+                let reversed: Vec<f64> = signal.iter().rev().copied().collect();
+                assert!(surrogates.iter().all(|s| *s == reversed));
+            }
+            // the other methods keep the distribution exactly
+            _ => assert_eq!(sorted(&surrogates[0]), sorted(&signal)),
+        }
+    }
+}
+
+#[test]
+fn test_surrogate_harness() {
+    use hrvhra_rust::samp_en::calc_samp_en;
+    use hrvhra_rust::surrogates::{surrogate_test, SurrogateConfig, SurrogateMethod};
+    // a strongly autocorrelated AR(1) series
+    let noise = lcg_series(1000, 29);
+    let mut ar = vec![0.0];
+    for e in &noise[1..] {
+        let last = *ar.last().unwrap();
+        ar.push(0.9 * last + (e - 800.0));
+    }
+    let lag1 = |x: &[f64]| {
+        let mean = x.iter().sum::<f64>() / x.len() as f64;
+        let var: f64 = x.iter().map(|v| (v - mean).powi(2)).sum();
+        x.windows(2)
+            .map(|w| (w[0] - mean) * (w[1] - mean))
+            .sum::<f64>()
+            / var
+    };
+    let shuffled = surrogate_test(
+        &ar,
+        &SurrogateConfig {
+            method: SurrogateMethod::Shuffle,
+            surrogates: 99,
+            seed: 1,
+        },
+        lag1,
+    );
+    assert!(shuffled.observed > 0.8);
+    assert_eq!(shuffled.p_value, 0.01);
+    assert!(shuffled.z_score > 10.0);
+    // IAAFT surrogates keep the linear correlations
+    let iaaft = surrogate_test(
+        &ar,
+        &SurrogateConfig {
+            surrogates: 19,
+            ..SurrogateConfig::default()
+        },
+        lag1,
+    );
+    assert!((iaaft.mean - iaaft.observed).abs() < 0.05);
+    // time reversal turns the slow rises of a sawtooth into slow falls
+    let sawtooth: Vec<f64> = (0..100).map(|i| 800.0 + 10.0 * (i % 5) as f64).collect();
+    let rises = |x: &[f64]| x.windows(2).filter(|w| w[1] > w[0]).count() as f64;
+    for surrogates in [1, 99] {
+        let reversed = surrogate_test(
+            &sawtooth,
+            &SurrogateConfig {
+                method: SurrogateMethod::TimeReversed,
+                surrogates,
+                seed: 0,
+            },
+            rises,
+        );
+        assert_eq!((reversed.observed, reversed.mean), (80.0, 19.0));
+        assert_eq!(reversed.difference(), 61.0);
+        // a single deterministic series has no distribution to rank against
+        assert!(reversed.sd.is_nan() && reversed.z_score.is_nan() && reversed.p_value.is_nan());
+    }
+    // sample entropy of white noise does not differ from its shuffles
+    let entropy = surrogate_test(
+        &noise,
+        &SurrogateConfig {
+            method: SurrogateMethod::Shuffle,
+            surrogates: 39,
+            seed: 3,
+        },
+        |x| calc_samp_en(x, 2, 0.2 * 115.0),
+    );
+    assert!(entropy.p_value > 0.05);
+    assert!(entropy.z_score.abs() < 2.5);
+}